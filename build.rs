@@ -0,0 +1,78 @@
+//! Generates the per-sample tests that `define_years!` includes.
+//!
+//! For every day module `src/yYY/dD.rs`, this writes `$OUT_DIR/samples/yYY/dD.rs`
+//! with one `#[test]` per `data/yYY/dD/partN/sampleK.in` that has a matching
+//! `sampleK.out`, so that a sample test is named like `y24::d16::part2::sample1`.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Parses the number out of a name like `d16`, `part2` or `sample1`.
+fn number_after(name: &str, prefix: &str) -> Option<usize> {
+    name.strip_prefix(prefix)?.parse().ok()
+}
+
+/// Lists the entries of a directory named `<prefix><number><suffix>`, sorted by the number.
+fn numbered_entries(dir: &Path, prefix: &str, suffix: &str) -> Vec<usize> {
+    let mut numbers: Vec<usize> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            number_after(name.strip_suffix(suffix)?, prefix)
+        })
+        .collect();
+
+    numbers.sort();
+    numbers
+}
+
+fn sample_tests(year: usize, day: usize) -> String {
+    let mut code = String::new();
+
+    for part in numbered_entries(Path::new(&format!("data/y{}/d{}", year, day)), "part", "") {
+        let dir = format!("data/y{}/d{}/part{}", year, day, part);
+
+        let samples: Vec<usize> = numbered_entries(Path::new(&dir), "sample", ".in")
+            .into_iter()
+            .filter(|sample| Path::new(&format!("{}/sample{}.out", dir, sample)).exists())
+            .collect();
+
+        if samples.is_empty() {
+            continue;
+        }
+
+        code += &format!("mod part{} {{\n", part);
+
+        for sample in samples {
+            code += &format!(
+                "    #[test]\n    fn sample{sample}() {{\n        \
+                 crate::runner::answers::assert_sample(&crate::y{year}::D{day}, {part}, \"{dir}/sample{sample}\");\n    \
+                 }}\n",
+            );
+        }
+
+        code += "}\n";
+    }
+
+    code
+}
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=data");
+    println!("cargo:rerun-if-changed=src");
+
+    for year in numbered_entries(Path::new("src"), "y", "") {
+        let year_dir = format!("{}/samples/y{}", out_dir, year);
+        fs::create_dir_all(&year_dir).unwrap();
+
+        for day in numbered_entries(Path::new(&format!("src/y{}", year)), "d", ".rs") {
+            fs::write(format!("{}/d{}.rs", year_dir, day), sample_tests(year, day)).unwrap();
+        }
+    }
+}
@@ -7,10 +7,12 @@ import numpy as np
 with open("timing_results.json", "r") as file:
     data = json.load(file)
 
-# Prepare the data for plotting
-days = [entry["day"] for entry in data]
-part1_times = [entry["times"]["1"][1] * 1000 for entry in data]
-part2_times = [entry["times"]["2"][1] * 1000 for entry in data]
+# Prepare the data for plotting (one record per part)
+times = {(entry["day"], entry["part"]): entry["seconds"] * 1000 for entry in data}
+
+days = sorted({day for day, _ in times})
+part1_times = [times.get((day, 1), 0) for day in days]
+part2_times = [times.get((day, 2), 0) for day in days]
 
 # Create the grouped bar plot
 x = np.arange(len(days))  # Positions for the groups
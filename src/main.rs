@@ -1,5 +1,7 @@
 #![feature(portable_simd)]
 
+mod runner;
+
 mod util;
 
 // Shared Intcode VM for AoC 2019 (used by y19 days 2,5,7,9,11,13,15,17,19,21).
@@ -24,11 +26,53 @@ macro_rules! define_years {
             () => {
                 vec![
                     $($(
-                        (Box::new($year::$day_pascal), stringify!($year), stringify!($day_snake)),
+                        (&$year::$day_pascal, stringify!($year), stringify!($day_snake)),
                     )*)*
                 ]
             }
         }
+
+        // One test per sample (e.g. `y24::d16::part2::sample1`), generated by `build.rs`.
+        #[cfg(test)]
+        mod sample_tests {
+            $(
+                mod $year {
+                    $(
+                        mod $day_snake {
+                            include!(concat!(
+                                env!("OUT_DIR"),
+                                "/samples/",
+                                stringify!($year),
+                                "/",
+                                stringify!($day_snake),
+                                ".rs"
+                            ));
+                        }
+                    )*
+                }
+            )*
+        }
+
+        // One regression test per day, checking the stored answers for the real input.
+        // These are slow in debug builds, so run them with `cargo test --release`.
+        #[cfg(test)]
+        mod answer_tests {
+            $(
+                mod $year {
+                    $(
+                        #[test]
+                        #[cfg_attr(debug_assertions, ignore)]
+                        fn $day_snake() {
+                            crate::runner::answers::assert_day(
+                                &crate::$year::$day_pascal,
+                                stringify!($year),
+                                stringify!($day_snake),
+                            );
+                        }
+                    )*
+                }
+            )*
+        }
     };
 }
 
@@ -109,21 +153,21 @@ define_years!(
     }
 );
 
-use crate::util::Day;
+use crate::runner::answers::{self, Verdict};
+use crate::runner::baseline;
+use crate::runner::bench::{self, Stats};
+use crate::runner::filter::{self, Selection};
+use crate::runner::guard::{self, Outcome};
+use crate::runner::report::{self, Format, Record, Status};
+use crate::runner::schedule;
+use crate::util::{AnyParsed, Error, Runnable};
 use clap::Parser;
 use colored::Colorize;
 use regex::Regex;
-use serde::Serialize;
-use std::collections::HashMap;
-use std::io::Write;
-use std::time::{Duration, Instant};
-
-#[derive(Serialize)]
-struct TimingResult {
-    day: usize,
-    times: HashMap<String, (String, f64)>,
-    year: usize,
-}
+use std::collections::BTreeMap;
+use std::io::{BufWriter, Write};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Gets the actual length of a string by stripping the ANSI escape codes.
 fn get_length_without_colors(input: &str) -> usize {
@@ -132,286 +176,761 @@ fn get_length_without_colors(input: &str) -> usize {
     clean_input.len()
 }
 
-/// Simple program to greet a person
-#[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
-struct Args {
-    #[arg(short, long, default_value_t = 0)]
-    year: usize,
+/// Colors the status of a checked or failed part, or returns `None` otherwise.
+fn colorize_status(status: Status) -> Option<colored::ColoredString> {
+    let name = status.to_string().to_uppercase();
 
-    #[arg(short, long, default_value_t = 0)]
-    day: usize,
+    match status {
+        Status::Pass => Some(name.green().bold()),
+        Status::Fail => Some(name.red().bold()),
+        Status::Missing => Some(name.yellow().bold()),
+        Status::Error | Status::Panic | Status::Timeout => Some(name.bright_red().bold()),
+        Status::Solved | Status::Unsolved => None,
+    }
 }
 
-fn main() {
-    let args = Args::parse();
-
-    let days: Vec<(Box<dyn Day>, &str, &str)> = days_vector!();
+/// Formats the timing of a part: a single duration, or the statistics in bench mode.
+fn format_stats(stats: &Stats) -> String {
+    let format = |seconds: f64| format!("{:.2?}", Duration::from_secs_f64(seconds));
+
+    if stats.iterations == 1 {
+        format(stats.median)
+    } else {
+        format!(
+            "median {}, min {}, mean {} ± {}",
+            format(stats.median),
+            format(stats.min),
+            format(stats.mean),
+            format(stats.stddev)
+        )
+    }
+}
 
-    let mut timing_results = Vec::new();
+/// Prints the answers and timings of a day.
+fn print_day(day: usize, records: &[Record]) {
+    let day_stars = records.iter().filter(|r| r.answer.is_some()).count();
 
-    let mut last_year = 0;
-    let mut last_stars = 0;
+    println!(
+        "{0} {1} {2} {0}",
+        "-".repeat(3).bright_black(),
+        format!("Day {}", day).bold(),
+        "*".repeat(day_stars).bright_yellow().bold(),
+    );
 
-    let mut total_time: f64 = 0.0;
-    let mut total_valid_stars: usize = 0;
+    // days with a parse phase of their own share it between the parts
+    if let Some(parse_seconds) = records.first().and_then(|r| r.parse_seconds) {
+        println!(
+            "{}",
+            format!("Parse: took {:.2?}", Duration::from_secs_f64(parse_seconds)).bright_black()
+        );
+    }
 
-    let mut year_times: HashMap<usize, (usize, f64)> = HashMap::new();
+    for record in records {
+        let status = match colorize_status(record.status) {
+            Some(status) => format!(" {}", status),
+            None => String::new(),
+        };
 
-    for (day_object, year_name, day_name) in days {
-        let year = year_name[1..].parse::<usize>().unwrap();
-        let day = day_name[1..].parse::<usize>().unwrap();
+        let Some(result) = &record.answer else {
+            // parts that failed, or have a stored answer but don't produce one anymore
+            match &record.message {
+                Some(message) => println!(
+                    "Part {}:{} {}",
+                    record.part.to_string().bold(),
+                    status,
+                    format!("({})", message).bright_black()
+                ),
+                None if record.status == Status::Fail => {
+                    println!("Part {}:{}", record.part.to_string().bold(), status)
+                }
+                None => {}
+            }
 
-        if args.year != 0 && args.year - 2000 != year && args.year != year {
             continue;
+        };
+
+        let mut time_string = match &record.stats {
+            Some(stats) => format_stats(stats),
+            None => format!("{:.2?}", Duration::from_secs_f64(record.seconds)),
+        };
+
+        if record.contended {
+            time_string += ", contended";
         }
 
-        if args.day != 0 && args.day != day {
-            continue;
+        // skip part 3s, since those are for fun
+        if record.part == 3 {
+            println!(
+                "{}",
+                format!("Part {}: {} (took {})", record.part, result, time_string).bright_black(),
+            );
+        } else {
+            println!(
+                "Part {}: {} (took {}){}",
+                record.part.to_string().bold(),
+                result.bright_blue(),
+                match record.seconds {
+                    s if s * 1000.0 < 1.0 => time_string.green(),
+                    s if s * 1000.0 < 10.0 => time_string.yellow(),
+                    _ => time_string.red(),
+                },
+                status,
+            );
         }
+    }
+
+    println!();
+}
+
+/// Prints the number of stars and the time taken per year and in total.
+fn print_summary(records: &[Record]) {
+    println!(
+        "{0}\n{1}\n{0}\n",
+        "---=======---".bright_black(),
+        "   Summary   ".bold(),
+    );
+
+    let solved = || records.iter().filter(|r| r.answer.is_some());
+
+    // the parse time is shared by the parts of a day, so it is counted once per day
+    let parse_times: BTreeMap<(usize, usize), f64> = records
+        .iter()
+        .filter_map(|r| Some(((r.year, r.day), r.parse_seconds?)))
+        .collect();
+
+    // part 3s are for fun, so they don't count as stars (or towards the total time)
+    let total_stars = solved().filter(|r| r.part <= 2).count();
+    let total_time: f64 = solved()
+        .filter(|r| r.part <= 2)
+        .map(|r| r.seconds)
+        .sum::<f64>()
+        + parse_times.values().sum::<f64>();
 
-        if year != last_year {
+    if total_time == 0.0 {
+        return;
+    }
+
+    let mut year_times: BTreeMap<usize, (usize, f64)> = BTreeMap::new();
+
+    for record in solved() {
+        let (count, time) = year_times.entry(record.year).or_default();
+
+        if record.part <= 2 {
+            *count += 1;
+        }
+
+        *time += record.seconds;
+    }
+
+    for ((year, _), seconds) in &parse_times {
+        year_times.entry(*year).or_default().1 += seconds;
+    }
+
+    for (year, (count, total)) in year_times {
+        println!(
+            "{}: {} ({})",
+            year,
+            format!("{}", count).bright_yellow().bold(),
+            format!("{:.2?}", Duration::from_secs_f64(total)).yellow()
+        );
+    }
+
+    let out = format!(
+        " all: {} ({})",
+        total_stars.to_string().bright_yellow().bold(),
+        format!("{:.2?}", Duration::from_secs_f64(total_time)).yellow()
+    );
+
+    println!(
+        "{}\n{}",
+        "-".repeat(get_length_without_colors(&out)).bright_black(),
+        out
+    );
+}
+
+/// Prints the parts that errored, panicked or timed out, separately from the unsolved ones.
+fn print_crashes(records: &[Record]) {
+    let count = |status: Status| records.iter().filter(|r| r.status == status).count();
+
+    let (errors, panics, timeouts) = (
+        count(Status::Error),
+        count(Status::Panic),
+        count(Status::Timeout),
+    );
+
+    if errors + panics + timeouts == 0 {
+        return;
+    }
+
+    println!(
+        "\n{} errored, {} panicked, {} timed out, {} unsolved",
+        errors.to_string().bright_red().bold(),
+        panics.to_string().bright_red().bold(),
+        timeouts.to_string().bright_red().bold(),
+        count(Status::Unsolved).to_string().bold(),
+    );
+
+    for record in records {
+        if let Some(message) = &record.message {
             println!(
-                "{0}\n{1}\n{0}\n",
-                "---====---".bright_black(),
-                format!("   20{}   ", year).bold(),
+                "{} {} day {} part {} ({})",
+                colorize_status(record.status).unwrap(),
+                record.year,
+                record.day,
+                record.part,
+                message
             );
+        }
+    }
+}
+
+/// Prints how many parts passed the check against the stored answers, and which failed.
+fn print_check(records: &[Record]) {
+    let count = |status: Status| records.iter().filter(|r| r.status == status).count();
+
+    let out = format!(
+        "check: {} passed, {} failed, {} missing",
+        count(Status::Pass).to_string().green().bold(),
+        count(Status::Fail).to_string().red().bold(),
+        count(Status::Missing).to_string().yellow().bold(),
+    );
+
+    println!(
+        "\n{}\n{}",
+        "-".repeat(get_length_without_colors(&out)).bright_black(),
+        out
+    );
 
-            last_year = year;
-            last_stars = total_valid_stars;
+    for record in records {
+        if let Some(expected) = &record.expected {
+            println!(
+                "{} {} day {} part {} (expected {})",
+                "FAIL".red().bold(),
+                record.year,
+                record.day,
+                record.part,
+                expected.bright_blue()
+            );
         }
+    }
+}
 
-        let input_file = format!("data/y{}/d{}/input.in", year, day);
-        let input = std::fs::read_to_string(&input_file)
-            .expect(&format!("Failed to read input file: {}", input_file));
+/// A day to run, along with the parts selected on the command line.
+struct Job {
+    day_object: &'static (dyn Runnable + Sync),
+    year: usize,
+    day: usize,
+    parts: Vec<usize>,
+}
 
-        let mut day_stars = 0;
-        let mut results = TimingResult {
-            day: day,
-            year: year,
-            times: Default::default(),
-        };
+/// How to run each part, as given on the command line.
+struct RunOptions {
+    warmup: usize,
+    iterations: usize,
+    check: bool,
+    bench: bool,
+    contended: bool,
+    timeout: Option<Duration>,
+}
 
-        // TODO: can this be done nicer, i.e. by iterating over the functions?
-        for part in 1..=3 {
-            let start = Instant::now();
-
-            let result = if part == 1 {
-                day_object.solve_part1(&input)
-            } else if part == 2 {
-                // day 25 part 2 is always special, since it only requires you to do the previous 49 challenges
-                if day == 25 {
-                    day_object.solve_part2(&*(total_valid_stars - last_stars).to_string())
-                } else {
-                    day_object.solve_part2(&input)
-                }
-            } else {
-                day_object.solve_part3(&input)
-            };
+impl RunOptions {
+    /// How long all the runs of a part (or of parsing) may take: the timeout for each.
+    fn time_limit(&self) -> Option<Duration> {
+        let runs = (self.warmup + self.iterations).max(1) as u32;
+        self.timeout.map(|timeout| timeout * runs)
+    }
+}
 
-            let duration = start.elapsed();
+/// Why a part was abandoned when it took too long.
+fn timeout_message(options: &RunOptions) -> String {
+    let timeout = options.timeout.unwrap();
 
-            match result {
-                Some(value) => {
-                    day_stars += 1;
+    if options.warmup + options.iterations > 1 {
+        format!("took longer than {:?} per run", timeout)
+    } else {
+        format!("took longer than {:?}", timeout)
+    }
+}
 
-                    if part <= 2 {
-                        total_valid_stars += 1;
-                    }
+/// Runs (and checks) a single part of a day on the input parsed by the day,
+/// guarding against it panicking or running for too long.
+fn run_part(
+    job: &Job,
+    part: usize,
+    parsed: Arc<AnyParsed>,
+    parse_seconds: Option<f64>,
+    options: &RunOptions,
+) -> Record {
+    let (warmup, iterations) = (options.warmup, options.iterations);
+    let day_object = job.day_object;
+
+    let outcome = guard::run(options.time_limit(), move || {
+        bench::measure(warmup, iterations, || day_object.solve(part, &parsed))
+    });
+
+    let (answer, stats, mut status, message) = match outcome {
+        Outcome::Finished((Ok(answer), stats)) => {
+            (Some(answer.to_string()), Some(stats), Status::Solved, None)
+        }
+        Outcome::Finished((Err(Error::NotImplemented), stats)) => {
+            (None, Some(stats), Status::Unsolved, None)
+        }
+        Outcome::Finished((Err(error), stats)) => {
+            (None, Some(stats), Status::Error, Some(error.to_string()))
+        }
+        Outcome::Panicked(message) => (None, None, Status::Panic, Some(message)),
+        Outcome::TimedOut => (None, None, Status::Timeout, Some(timeout_message(options))),
+    };
 
-                    results
-                        .times
-                        .insert(part.to_string(), (value, duration.as_secs_f64()));
-                }
-                None => {}
+    let mut expected = None;
+
+    if options.check && part <= 2 && message.is_none() {
+        match answers::check(job.year, job.day, part, answer.as_deref()) {
+            Some(Verdict::Pass) => status = Status::Pass,
+            Some(Verdict::Fail { expected: stored }) => {
+                status = Status::Fail;
+                expected = Some(stored);
             }
+            Some(Verdict::Missing) => status = Status::Missing,
+            None => {}
         }
+    }
+
+    Record {
+        year: 2000 + job.year,
+        day: job.day,
+        part,
+        answer,
+        seconds: stats.map_or(0.0, |stats| stats.median),
+        parse_seconds,
+        status,
+        expected,
+        message,
+        stats: stats.filter(|_| options.bench),
+        contended: options.contended,
+    }
+}
+
+/// Parses the input of a day once (timing it separately for days that have a parse
+/// phase of their own) and runs the given parts on it. If the parsing fails, so do
+/// all of the parts.
+fn run_input(job: &Job, parts: &[usize], input: Arc<str>, options: &RunOptions) -> Vec<Record> {
+    if parts.is_empty() {
+        return Vec::new();
+    }
+
+    let (warmup, iterations) = (options.warmup, options.iterations);
+    let day_object = job.day_object;
+
+    let outcome = guard::run(options.time_limit(), move || {
+        bench::measure(warmup, iterations, || day_object.parse(&input))
+    });
+
+    let (status, message) = match outcome {
+        Outcome::Finished((Ok(parsed), stats)) => {
+            let parsed = Arc::new(parsed);
+            let parse_seconds = day_object.parses().then_some(stats.median);
 
-        if day_stars > 0 {
-            year_times
-                .entry(year)
-                .and_modify(|(count, time)| {
-                    *count += day_stars.min(2); // no part 3s
-                    *time += results
-                        .times
-                        .values()
-                        .filter(|&&(ref _res, t)| t > 0.0)
-                        .map(|&(_, t)| t)
-                        .sum::<f64>();
-                })
-                .or_insert((
-                    day_stars.min(2), // no part 3s
-                    results
-                        .times
-                        .values()
-                        .filter(|&&(ref _res, t)| t > 0.0)
-                        .map(|&(_, t)| t)
-                        .sum(),
-                ));
+            return parts
+                .iter()
+                .map(|&part| run_part(job, part, parsed.clone(), parse_seconds, options))
+                .collect();
         }
+        Outcome::Finished((Err(error), _)) => (Status::Error, error.to_string()),
+        Outcome::Panicked(message) => (Status::Panic, message),
+        Outcome::TimedOut => (Status::Timeout, timeout_message(options)),
+    };
 
-        println!(
-            "{0} {1} {2} {0}",
-            "-".repeat(3).bright_black(),
-            format!("Day {}", day).bold(),
-            "*".repeat(day_stars).bright_yellow().bold(),
+    parts
+        .iter()
+        .map(|&part| Record {
+            year: 2000 + job.year,
+            day: job.day,
+            part,
+            answer: None,
+            seconds: 0.0,
+            parse_seconds: None,
+            status,
+            expected: None,
+            message: Some(format!("while parsing: {}", message)),
+            stats: None,
+            contended: options.contended,
+        })
+        .collect()
+}
+
+/// Runs the selected parts of a day, except for day 25 part 2 (see `main`).
+fn run_day(job: &Job, options: &RunOptions) -> Vec<Record> {
+    let input_file = format!("data/y{}/d{}/input.in", job.year, job.day);
+    let input: Arc<str> = std::fs::read_to_string(&input_file)
+        .unwrap_or_else(|_| panic!("Failed to read input file: {}", input_file))
+        .into();
+
+    let parts: Vec<usize> = job
+        .parts
+        .iter()
+        .copied()
+        .filter(|&part| !(job.day == 25 && part == 2))
+        .collect();
+
+    run_input(job, &parts, input, options)
+        .into_iter()
+        // most days have no part 3, so an unsolved one isn't worth reporting
+        .filter(|record| record.part != 3 || record.answer.is_some())
+        .collect()
+}
+
+/// Runs the Advent of Code solutions and reports their answers and timings
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Years to run, e.g. `2024`, `2019..2021` or `15,17` (all by default)
+    #[arg(short, long, value_parser = filter::parse_years)]
+    year: Option<Selection>,
+
+    /// Days to run, e.g. `1,5,20-25` (all by default)
+    #[arg(short, long)]
+    day: Option<Selection>,
+
+    /// Parts to run, e.g. `2` or `1,2` (all by default)
+    #[arg(short, long)]
+    part: Option<Selection>,
+
+    /// Skip the optional part 3s, which are just for fun
+    #[arg(long)]
+    skip_fun: bool,
+
+    /// Only list the selected parts that are unsolved (return no answer)
+    #[arg(short, long)]
+    unsolved: bool,
+
+    /// Compare every solved part against the stored answers
+    #[arg(short, long)]
+    check: bool,
+
+    /// Store the answers of solved parts that don't have a stored answer yet
+    #[arg(long)]
+    record: bool,
+
+    /// Time each part over several runs and report statistics instead of a single run
+    #[arg(short, long)]
+    bench: bool,
+
+    /// Number of timed runs per part in bench mode
+    #[arg(long, default_value_t = 10)]
+    iterations: usize,
+
+    /// Number of untimed warm-up runs per part in bench mode
+    #[arg(long, default_value_t = 2)]
+    warmup: usize,
+
+    /// Save the timings of this run as a named baseline (in `baselines/<NAME>.json`)
+    #[arg(long, value_name = "NAME")]
+    save_baseline: Option<String>,
+
+    /// Compare the timings of this run against a baseline, failing on regressions
+    #[arg(long, value_name = "BASELINE.json")]
+    compare: Option<String>,
+
+    /// Slowdown (in percent) above which a part counts as a regression
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+
+    /// Format of the results
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Write the results to this file instead of stdout (the text report is then still printed)
+    #[arg(short, long)]
+    output: Option<String>,
+
+    /// Number of days to run concurrently (their timings are then marked as contended)
+    #[arg(short, long, default_value_t = 1)]
+    jobs: usize,
+
+    /// Seconds after which a part is abandoned as timed out, per run in bench mode (0 to
+    /// wait forever)
+    #[arg(short, long, default_value_t = 60.0)]
+    timeout: f64,
+
+    /// Debug the Intcode program of the selected 2019 day in an interactive prompt
+    #[arg(long)]
+    debug: bool,
+}
+
+/// Starts the Intcode debugger on the input of the single selected day, which has to
+/// be one of the 2019 Intcode days.
+fn debug(jobs: &[Job]) {
+    let [job] = jobs else {
+        eprintln!("--debug needs a single day, e.g. -y 2019 -d 13");
+        std::process::exit(2);
+    };
+
+    if job.year != 19 || !y19_intcode::debug::DAYS.contains(&job.day) {
+        eprintln!(
+            "--debug only works for the 2019 Intcode days ({:?})",
+            y19_intcode::debug::DAYS
         );
+        std::process::exit(2);
+    }
 
-        let mut sorted_keys: Vec<String> = results.times.keys().cloned().collect();
-        sorted_keys.sort();
+    let input_file = format!("data/y{}/d{}/input.in", job.year, job.day);
+    let input = std::fs::read_to_string(&input_file)
+        .unwrap_or_else(|_| panic!("Failed to read input file: {}", input_file));
 
-        for part in sorted_keys {
-            if let Some((result, seconds)) = results.times.get(&part) {
-                // skip part 3s, since those are for fun
-                if part == "3" {
-                    println!(
-                        "{}",
-                        format!(
-                            "Part {}: {} (took {})",
-                            part,
-                            result,
-                            format!("{:.2?}", Duration::from_secs_f64(*seconds))
-                        )
-                        .bright_black(),
-                    );
-                } else {
-                    let time_string = format!("{:.2?}", Duration::from_secs_f64(*seconds));
+    if let Err(error) = y19_intcode::debug::start(&input) {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+
+    if args.format == Format::Text && args.output.is_some() {
+        eprintln!("--output requires a --format other than text");
+        std::process::exit(2);
+    }
+
+    // the text report is printed unless stdout is taken by another format
+    let text = args.format == Format::Text || args.output.is_some();
+
+    let (warmup, iterations) = if args.bench {
+        (args.warmup, args.iterations)
+    } else {
+        (0, 1)
+    };
+
+    let options = RunOptions {
+        warmup,
+        iterations,
+        check: args.check,
+        bench: args.bench,
+        contended: args.jobs > 1,
+        timeout: (args.timeout > 0.0).then(|| Duration::from_secs_f64(args.timeout)),
+    };
+
+    let days: Vec<(&'static (dyn Runnable + Sync), &str, &str)> = days_vector!();
+
+    let jobs: Vec<Job> = days
+        .iter()
+        .filter_map(|(day_object, year_name, day_name)| {
+            let year = year_name[1..].parse::<usize>().unwrap();
+            let day = day_name[1..].parse::<usize>().unwrap();
+
+            if args
+                .year
+                .as_ref()
+                .is_some_and(|years| !years.contains(year))
+                || args.day.as_ref().is_some_and(|days| !days.contains(day))
+            {
+                return None;
+            }
+
+            let parts = (1..=3)
+                .filter(|&part| args.part.as_ref().is_none_or(|parts| parts.contains(part)))
+                .filter(|&part| part != 3 || !args.skip_fun)
+                .collect();
+
+            Some(Job {
+                day_object: *day_object,
+                year,
+                day,
+                parts,
+            })
+        })
+        .collect();
+
+    if args.debug {
+        debug(&jobs);
+        return;
+    }
+
+    let mut records: Vec<Record> = Vec::new();
+
+    let mut last_year = 0;
+    let mut last_stars = 0;
+
+    let mut total_valid_stars: usize = 0;
 
-                    total_time += seconds;
+    schedule::run_ordered(
+        &jobs,
+        args.jobs,
+        |job| run_day(job, &options),
+        |job, mut day_records| {
+            if job.year != last_year {
+                if text && !args.unsolved {
                     println!(
-                        "Part {}: {} (took {})",
-                        part.bold(),
-                        result.bright_blue(),
-                        match seconds {
-                            &s if s * 1000.0 < 1.0 => time_string.green(),
-                            &s if s * 1000.0 < 10.0 => time_string.yellow(),
-                            _ => time_string.red(),
-                        },
+                        "{0}\n{1}\n{0}\n",
+                        "---====---".bright_black(),
+                        format!("   20{}   ", job.year).bold(),
                     );
                 }
+
+                last_year = job.year;
+                last_stars = total_valid_stars;
             }
-        }
 
-        println!();
+            let day_stars = |records: &[Record]| {
+                records
+                    .iter()
+                    .filter(|r| r.part <= 2 && r.answer.is_some())
+                    .count()
+            };
 
-        timing_results.push(results);
-    }
+            // day 25 part 2 is always special, since it only requires you to do the previous 49 challenges,
+            // so it is only run once all of them are done
+            if job.day == 25 && job.parts.contains(&2) {
+                let stars = total_valid_stars - last_stars + day_stars(&day_records);
+                let record = run_input(job, &[2], stars.to_string().into(), &options).remove(0);
 
-    println!(
-        "{0}\n{1}\n{0}\n",
-        "---=======---".bright_black(),
-        format!("   Summary   ").bold(),
+                let position = day_records.iter().take_while(|r| r.part < 2).count();
+                day_records.insert(position, record);
+            }
+
+            total_valid_stars += day_stars(&day_records);
+
+            if args.record {
+                for record in &day_records {
+                    let Some(value) = &record.answer else {
+                        continue;
+                    };
+
+                    if record.part <= 2
+                        && answers::record(job.year, job.day, record.part, value)
+                        && text
+                    {
+                        println!(
+                            "{}",
+                            format!(
+                                "Recorded {}",
+                                answers::answer_path(job.year, job.day, record.part)
+                            )
+                            .bright_black()
+                        );
+                    }
+                }
+            }
+
+            if text && !args.unsolved {
+                print_day(job.day, &day_records);
+            }
+
+            records.extend(day_records);
+        },
     );
 
-    if total_time != 0.0 {
-        let mut sorted_keys: Vec<usize> = year_times.keys().cloned().collect::<Vec<_>>();
-        sorted_keys.sort();
+    let mut regressions = 0;
+
+    if args.unsolved {
+        records.retain(|r| r.part <= 2 && r.answer.is_none());
 
-        for key in sorted_keys {
-            if let Some(&(count, total)) = year_times.get(&key) {
+        if text {
+            for record in &records {
                 println!(
-                    "20{}: {} ({})",
-                    key,
-                    format!("{}", count).bright_yellow().bold(),
-                    format!("{:.2?}", Duration::from_secs_f64(total)).yellow()
+                    "{} day {} part {}{}",
+                    record.year,
+                    record.day,
+                    record.part,
+                    match colorize_status(record.status) {
+                        Some(status) => format!(" {}", status),
+                        None => String::new(),
+                    }
                 );
             }
+
+            println!(
+                "{}",
+                format!("{} unsolved parts", records.len()).bright_black()
+            );
         }
+    } else {
+        if text {
+            print_summary(&records);
+            print_crashes(&records);
 
-        let out = format!(
-            " all: {} ({})",
-            total_valid_stars.to_string().bright_yellow().bold(),
-            format!("{:.2?}", Duration::from_secs_f64(total_time)).yellow()
-        );
+            if args.check {
+                print_check(&records);
+            }
+        }
 
-        println!(
-            "{}\n{}",
-            "-".repeat(get_length_without_colors(&out)).bright_black(),
-            out
-        );
-    }
+        if let Some(path) = &args.compare {
+            let deltas = baseline::compare(&baseline::load(path), &records);
 
-    let json_file = "timing_results.json";
-    let json_data = serde_json::to_string_pretty(&timing_results)
-        .expect("Failed to serialize timing results to JSON");
-    let mut file = std::fs::File::create(json_file).expect("Failed to create JSON file");
-    file.write_all(json_data.as_bytes())
-        .expect("Failed to write JSON data to file");
-}
+            regressions = deltas
+                .iter()
+                .filter(|delta| delta.is_regression(args.threshold))
+                .count();
+
+            if text {
+                println!(
+                    "\n{0}\n{1}\n{0}\n",
+                    "---=======---".bright_black(),
+                    "   Compare   ".bold(),
+                );
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use std::option::Option;
-
-    fn find_samples(dir: &str) -> Vec<(String, String)> {
-        let mut samples = vec![];
-
-        if let Ok(entries) = fs::read_dir(dir) {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let path = entry.path();
-                    if let Some(file_name) = path.file_name().and_then(|s| s.to_str()) {
-                        if file_name.starts_with("sample") && file_name.ends_with(".in") {
-                            let base_name = &file_name[..file_name.len() - 3];
-                            let input_file = path.clone();
-                            let output_file = path.with_file_name(format!("{}.out", base_name));
-
-                            if output_file.exists() {
-                                samples.push((
-                                    input_file.to_str().unwrap().to_string(),
-                                    output_file.to_str().unwrap().to_string(),
-                                ));
-                            }
+                for delta in &deltas {
+                    let change = format!(
+                        "{:.2?} -> {:.2?} ({:+.1}%)",
+                        Duration::from_secs_f64(delta.before),
+                        Duration::from_secs_f64(delta.after),
+                        delta.percent()
+                    );
+
+                    println!(
+                        "{} day {} part {}: {}",
+                        delta.year,
+                        delta.day,
+                        delta.part,
+                        if delta.is_regression(args.threshold) {
+                            change.red().bold()
+                        } else if delta.is_improvement(args.threshold) {
+                            change.green()
+                        } else {
+                            change.normal()
                         }
-                    }
+                    );
                 }
+
+                let out = format!(
+                    "compare: {} regressions above {}% ({} parts compared)",
+                    regressions.to_string().red().bold(),
+                    args.threshold,
+                    deltas.len()
+                );
+
+                println!(
+                    "{}\n{}",
+                    "-".repeat(get_length_without_colors(&out)).bright_black(),
+                    out
+                );
             }
         }
 
-        samples
+        if let Some(name) = &args.save_baseline {
+            let path = baseline::save(name, &records);
+
+            if text {
+                println!("{}", format!("Saved baseline {}", path).bright_black());
+            }
+        }
     }
 
-    #[test]
-    fn test_all_days() {
-        let days: Vec<(Box<dyn Day>, &str, &str)> = days_vector!();
+    if args.format != Format::Text {
+        let mut out: Box<dyn Write> = match &args.output {
+            Some(path) => Box::new(BufWriter::new(
+                std::fs::File::create(path)
+                    .unwrap_or_else(|_| panic!("Failed to create output file: {}", path)),
+            )),
+            None => Box::new(std::io::stdout().lock()),
+        };
 
-        for (day_object, year_name, day_name) in days {
-            let year = year_name[1..].parse::<usize>().unwrap();
-            let day = day_name[1..].parse::<usize>().unwrap();
+        report::write(args.format, &records, &mut out)
+            .and_then(|_| out.flush())
+            .expect("Failed to write the results");
+    }
 
-            for part in ["part1", "part2"] {
-                let samples = find_samples(format!("data/y{}/d{}/{}", year, day, part).as_str());
-
-                for (input_file, output_file) in &samples {
-                    let input = fs::read_to_string(input_file)
-                        .expect(&format!("Failed to read input file: {}", input_file));
-
-                    let expected_output = fs::read_to_string(output_file)
-                        .expect(&format!("Failed to read output file: {}", output_file))
-                        .trim()
-                        .to_string(); // Convert to String
-
-                    if part == "part1" {
-                        assert_eq!(
-                            day_object.solve_part1(&input),
-                            Option::from(expected_output),
-                            "{}, {} failed",
-                            day_name,
-                            part,
-                        );
-                    } else {
-                        assert_eq!(
-                            day_object.solve_part2(&input),
-                            Option::from(expected_output),
-                            "{}, {} failed",
-                            day_name,
-                            part,
-                        );
-                    }
-                }
-            }
-        }
+    if regressions > 0 || records.iter().any(|r| r.status.is_failure()) {
+        std::process::exit(1);
     }
 }
@@ -0,0 +1,9 @@
+//! Pieces of the command-line runner that `main` is built from.
+
+pub mod answers;
+pub mod baseline;
+pub mod bench;
+pub mod filter;
+pub mod guard;
+pub mod report;
+pub mod schedule;
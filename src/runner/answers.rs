@@ -0,0 +1,137 @@
+//! Stored answers for the real puzzle inputs.
+//!
+//! Next to each `data/yYY/dD/input.in` there may be an `answer1.out` and an
+//! `answer2.out` holding the accepted answer for that part. They are used by the
+//! runner's `--check` mode and by the per-day regression tests in `main.rs`.
+//! The samples in `data/yYY/dD/partN/sampleK.{in,out}` get a test each, too.
+
+#[cfg(test)]
+use crate::util::Runnable;
+use std::fmt;
+use std::fs;
+
+/// The outcome of comparing a part's answer against the stored one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// The answer matches the stored one.
+    Pass,
+    /// The answer differs from the stored one (or the part no longer returns one).
+    Fail { expected: String },
+    /// The part returned an answer, but there is nothing stored to compare it to.
+    Missing,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail { .. } => write!(f, "FAIL"),
+            Verdict::Missing => write!(f, "MISSING"),
+        }
+    }
+}
+
+pub fn answer_path(year: usize, day: usize, part: usize) -> String {
+    format!("data/y{}/d{}/answer{}.out", year, day, part)
+}
+
+/// Normalizes an answer so that multi-line (ASCII art) answers compare equal
+/// regardless of trailing whitespace.
+fn normalize(answer: &str) -> String {
+    answer
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_matches('\n')
+        .to_string()
+}
+
+/// Loads the stored answer for the given part, if there is one.
+pub fn load(year: usize, day: usize, part: usize) -> Option<String> {
+    fs::read_to_string(answer_path(year, day, part))
+        .ok()
+        .map(|answer| normalize(&answer))
+}
+
+/// Stores an answer for the given part, unless one is stored already.
+/// Returns whether the file was written.
+pub fn record(year: usize, day: usize, part: usize, answer: &str) -> bool {
+    let path = answer_path(year, day, part);
+
+    if fs::metadata(&path).is_ok() {
+        return false;
+    }
+
+    fs::write(&path, normalize(answer) + "\n")
+        .unwrap_or_else(|_| panic!("Failed to write answer file: {}", path));
+
+    true
+}
+
+/// Compares an answer against the stored one. Returns `None` if there is
+/// neither a stored answer nor an actual one (i.e. the part is unsolved).
+pub fn check(year: usize, day: usize, part: usize, actual: Option<&str>) -> Option<Verdict> {
+    match (load(year, day, part), actual) {
+        (Some(expected), Some(actual)) if expected == normalize(actual) => Some(Verdict::Pass),
+        (Some(expected), _) => Some(Verdict::Fail { expected }),
+        (None, Some(_)) => Some(Verdict::Missing),
+        (None, None) => None,
+    }
+}
+
+/// Asserts that every part of a day with a stored answer still produces it.
+/// Day 25 part 2 is skipped, since its input is the number of stars collected.
+#[cfg(test)]
+pub fn assert_day(day_object: &dyn Runnable, year_name: &str, day_name: &str) {
+    let year = year_name[1..].parse::<usize>().unwrap();
+    let day = day_name[1..].parse::<usize>().unwrap();
+
+    let input_file = format!("data/y{}/d{}/input.in", year, day);
+    let Ok(input) = fs::read_to_string(&input_file) else {
+        return;
+    };
+
+    for part in 1..=2 {
+        if part == 2 && day == 25 {
+            continue;
+        }
+
+        let Some(expected) = load(year, day, part) else {
+            continue;
+        };
+
+        let actual = day_object.run(part, &input);
+
+        assert_eq!(
+            actual.map(|answer| normalize(&answer.to_string())),
+            Ok(expected),
+            "{}::{}, part{} failed",
+            year_name,
+            day_name,
+            part,
+        );
+    }
+}
+
+/// Asserts that a part produces the expected output for a sample, where `sample`
+/// is the path of the sample without the `.in`/`.out` extension.
+#[cfg(test)]
+pub fn assert_sample(day_object: &dyn Runnable, part: usize, sample: &str) {
+    let input_file = format!("{}.in", sample);
+    let input = fs::read_to_string(&input_file)
+        .unwrap_or_else(|_| panic!("Failed to read input file: {}", input_file));
+
+    let output_file = format!("{}.out", sample);
+    let expected = fs::read_to_string(&output_file)
+        .unwrap_or_else(|_| panic!("Failed to read output file: {}", output_file));
+
+    let actual = day_object.run(part, &input);
+
+    assert_eq!(
+        actual.map(|answer| normalize(&answer.to_string())),
+        Ok(normalize(&expected)),
+        "{} failed",
+        sample,
+    );
+}
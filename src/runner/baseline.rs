@@ -0,0 +1,133 @@
+//! Saving timing baselines and comparing a run against one.
+//!
+//! A baseline is simply the JSON records of an earlier run (see `report`), saved
+//! under `baselines/<name>.json` with `--save-baseline <name>`. With
+//! `--compare <file>`, the (median) time of every solved part is compared to the
+//! baseline's.
+
+use crate::runner::report::Record;
+use std::collections::HashMap;
+use std::fs;
+
+pub const BASELINE_DIR: &str = "baselines";
+
+/// The change in the median time of a single part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delta {
+    pub year: usize,
+    pub day: usize,
+    pub part: usize,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl Delta {
+    /// The relative change in percent (positive means slower).
+    pub fn percent(&self) -> f64 {
+        if self.before <= 0.0 {
+            return 0.0;
+        }
+
+        (self.after - self.before) / self.before * 100.0
+    }
+
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.percent() > threshold
+    }
+
+    pub fn is_improvement(&self, threshold: f64) -> bool {
+        self.percent() < -threshold
+    }
+}
+
+pub fn baseline_path(name: &str) -> String {
+    format!("{}/{}.json", BASELINE_DIR, name)
+}
+
+/// Saves the results of a run as a named baseline, returning the path it was saved to.
+pub fn save(name: &str, records: &[Record]) -> String {
+    let path = baseline_path(name);
+
+    fs::create_dir_all(BASELINE_DIR).expect("Failed to create the baseline directory");
+
+    let json_data =
+        serde_json::to_string_pretty(records).expect("Failed to serialize timing results to JSON");
+    fs::write(&path, json_data).unwrap_or_else(|_| panic!("Failed to write baseline: {}", path));
+
+    path
+}
+
+pub fn load(path: &str) -> Vec<Record> {
+    let json_data =
+        fs::read_to_string(path).unwrap_or_else(|_| panic!("Failed to read baseline: {}", path));
+
+    serde_json::from_str(&json_data).unwrap_or_else(|e| panic!("Invalid baseline {}: {}", path, e))
+}
+
+/// Compares the parts solved in both the baseline and the current run,
+/// in the order of the current run.
+pub fn compare(baseline: &[Record], records: &[Record]) -> Vec<Delta> {
+    let before: HashMap<(usize, usize, usize), &Record> = baseline
+        .iter()
+        .filter(|record| record.answer.is_some())
+        .map(|record| ((record.year, record.day, record.part), record))
+        .collect();
+
+    records
+        .iter()
+        .filter(|record| record.answer.is_some())
+        .filter_map(|record| {
+            let old = before.get(&(record.year, record.day, record.part))?;
+
+            Some(Delta {
+                year: record.year,
+                day: record.day,
+                part: record.part,
+                before: old.seconds,
+                after: record.seconds,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::report::Status;
+
+    fn record(day: usize, part: usize, seconds: f64) -> Record {
+        Record {
+            year: 2024,
+            day,
+            part,
+            answer: Some(String::new()),
+            seconds,
+            parse_seconds: None,
+            status: Status::Solved,
+            expected: None,
+            message: None,
+            stats: None,
+            contended: false,
+        }
+    }
+
+    #[test]
+    fn compares_matching_parts() {
+        let baseline = vec![record(1, 1, 1.0), record(1, 2, 2.0)];
+        let records = vec![
+            record(1, 1, 1.5),
+            record(1, 2, 1.0),
+            record(1, 3, 1.0),
+            record(2, 1, 1.0),
+        ];
+
+        let deltas = compare(&baseline, &records);
+
+        assert_eq!(deltas.len(), 2);
+        assert_eq!(deltas[0].percent(), 50.0);
+        assert!(deltas[0].is_regression(10.0));
+        assert!(!deltas[0].is_regression(60.0));
+        assert_eq!(deltas[1].percent(), -50.0);
+        assert!(deltas[1].is_improvement(10.0));
+    }
+}
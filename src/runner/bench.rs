@@ -0,0 +1,102 @@
+//! Repeated timing of a part, for the runner's `--bench` mode.
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Statistics over the timed runs of a part, in seconds.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub iterations: usize,
+}
+
+impl Stats {
+    /// Computes the statistics of the given (non-empty) list of timings.
+    pub fn from_samples(samples: &[f64]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let n = sorted.len();
+
+        let median = if n % 2 == 1 {
+            sorted[n / 2]
+        } else {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        };
+
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+
+        // sample standard deviation; a single run has none
+        let stddev = if n > 1 {
+            (sorted.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean,
+            stddev,
+            iterations: n,
+        }
+    }
+}
+
+/// Runs `f` `warmup` times untimed, then `iterations` (at least one) times timed.
+/// Returns the result of the last run along with the timing statistics.
+pub fn measure<R>(warmup: usize, iterations: usize, mut f: impl FnMut() -> R) -> (R, Stats) {
+    for _ in 0..warmup {
+        f();
+    }
+
+    let mut samples = Vec::with_capacity(iterations.max(1));
+
+    loop {
+        let start = Instant::now();
+        let result = f();
+        samples.push(start.elapsed().as_secs_f64());
+
+        if samples.len() >= iterations {
+            return (result, Stats::from_samples(&samples));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_of_samples() {
+        let stats = Stats::from_samples(&[4.0, 1.0, 3.0, 2.0]);
+
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.median, 2.5);
+        assert_eq!(stats.mean, 2.5);
+        assert!((stats.stddev - (5.0f64 / 3.0).sqrt()).abs() < 1e-12);
+        assert_eq!(stats.iterations, 4);
+    }
+
+    #[test]
+    fn single_run_has_no_deviation() {
+        let (result, stats) = measure(0, 1, || 42);
+
+        assert_eq!(result, 42);
+        assert_eq!(stats.iterations, 1);
+        assert_eq!(stats.stddev, 0.0);
+        assert_eq!(stats.min, stats.median);
+    }
+
+    #[test]
+    fn warmup_runs_are_not_timed() {
+        let mut runs = 0;
+        let (_, stats) = measure(3, 5, || runs += 1);
+
+        assert_eq!(runs, 8);
+        assert_eq!(stats.iterations, 5);
+    }
+}
@@ -0,0 +1,96 @@
+//! Selections of years, days and parts given on the command line, such as
+//! `--year 2019..2021`, `--day 1,5,20-25` or `--part 2`.
+
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// A comma-separated list of numbers and inclusive ranges (`a-b` or `a..b`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection(Vec<RangeInclusive<usize>>);
+
+impl Selection {
+    pub fn contains(&self, n: usize) -> bool {
+        self.0.iter().any(|range| range.contains(&n))
+    }
+
+    fn map(self, f: impl Fn(usize) -> usize) -> Selection {
+        Selection(
+            self.0
+                .into_iter()
+                .map(|range| f(*range.start())..=f(*range.end()))
+                .collect(),
+        )
+    }
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &str| {
+            n.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("'{}' is not a number", n.trim()))
+        };
+
+        s.split(',')
+            .map(|item| {
+                let range = match item.split_once("..").or_else(|| item.split_once('-')) {
+                    Some((start, end)) => number(start)?..=number(end)?,
+                    None => number(item)?..=number(item)?,
+                };
+
+                if range.is_empty() {
+                    return Err(format!("'{}' is an empty range", item.trim()));
+                }
+
+                Ok(range)
+            })
+            .collect::<Result<_, _>>()
+            .map(Selection)
+    }
+}
+
+/// Parses a selection of years, which may be given either as `2019` or as `19`.
+pub fn parse_years(s: &str) -> Result<Selection, String> {
+    let selection: Selection = s.parse()?;
+
+    Ok(selection.map(|year| if year >= 2000 { year - 2000 } else { year }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_and_ranges() {
+        let days: Selection = "1,5,20-25".parse().unwrap();
+
+        assert!(days.contains(1));
+        assert!(!days.contains(2));
+        assert!(days.contains(5));
+        assert!(days.contains(20));
+        assert!(days.contains(25));
+        assert!(!days.contains(19));
+    }
+
+    #[test]
+    fn years_in_either_form() {
+        let years = parse_years("2019..2021").unwrap();
+
+        assert!(years.contains(19));
+        assert!(years.contains(21));
+        assert!(!years.contains(22));
+        assert_eq!(
+            parse_years("15-16").unwrap(),
+            parse_years("2015-2016").unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_selections() {
+        assert!("1,x".parse::<Selection>().is_err());
+        assert!("5-3".parse::<Selection>().is_err());
+        assert!("".parse::<Selection>().is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! Running a part so that a panic or a runaway computation doesn't take down the
+//! whole run.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// The stack size of the thread a computation with a timeout runs on: as much as
+/// the main thread usually gets, so that deep recursion works either way.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// How a guarded computation ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<R> {
+    Finished(R),
+    /// The computation panicked, with the given message.
+    Panicked(String),
+    /// The computation didn't finish in time.
+    TimedOut,
+}
+
+/// Gets the message out of a panic payload.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Runs `f`, catching any panic. With a timeout, `f` runs on its own thread and is
+/// abandoned if it doesn't finish in time; since a thread can't be killed, it keeps
+/// running in the background until the program exits.
+pub fn run<R: Send + 'static>(
+    timeout: Option<Duration>,
+    f: impl FnOnce() -> R + Send + 'static,
+) -> Outcome<R> {
+    let guarded = move || match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => Outcome::Finished(result),
+        Err(payload) => Outcome::Panicked(panic_message(payload)),
+    };
+
+    let Some(timeout) = timeout else {
+        return guarded();
+    };
+
+    let (sender, receiver) = mpsc::channel();
+
+    thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let _ = sender.send(guarded());
+        })
+        .expect("Failed to spawn a thread");
+
+    receiver.recv_timeout(timeout).unwrap_or(Outcome::TimedOut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finished() {
+        assert_eq!(run(None, || 42), Outcome::Finished(42));
+        assert_eq!(
+            run(Some(Duration::from_secs(10)), || 42),
+            Outcome::Finished(42)
+        );
+    }
+
+    #[test]
+    fn panicked() {
+        assert_eq!(
+            run(None, || -> usize { panic!("oh no {}", 1) }),
+            Outcome::Panicked("oh no 1".to_string())
+        );
+        assert_eq!(
+            run(Some(Duration::from_secs(10)), || -> usize {
+                panic!("oh no")
+            }),
+            Outcome::Panicked("oh no".to_string())
+        );
+    }
+
+    #[test]
+    fn recurses_deeply() {
+        // takes more than the 2 MiB a spawned thread gets by default
+        fn depth(n: u64) -> u64 {
+            let frame = std::hint::black_box([n; 64]);
+            if n == 0 {
+                0
+            } else {
+                1 + depth(frame[n as usize % 64] - 1)
+            }
+        }
+
+        assert_eq!(
+            run(Some(Duration::from_secs(10)), || depth(5_000)),
+            Outcome::Finished(5_000)
+        );
+    }
+
+    #[test]
+    fn timed_out() {
+        assert_eq!(
+            run(Some(Duration::from_millis(10)), || {
+                thread::sleep(Duration::from_secs(1));
+            }),
+            Outcome::TimedOut
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Machine-readable results of a run, in the formats selectable with `--format`.
+//!
+//! Every part that was run becomes one [`Record`] with the same named fields in
+//! every format: `year`, `day`, `part`, `answer`, `seconds` and `status`.
+
+use crate::runner::bench::Stats;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{self, Write};
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Colored, human-readable text
+    Text,
+    /// A JSON array of records
+    Json,
+    /// One JSON record per line
+    Jsonl,
+    /// Comma-separated values with a header line
+    Csv,
+    /// Test Anything Protocol
+    Tap,
+    /// JUnit XML
+    Junit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// The part produced an answer.
+    Solved,
+    /// The part isn't implemented (yet).
+    Unsolved,
+    /// The part failed to produce an answer, e.g. because the input didn't parse.
+    Error,
+    /// The answer matches the stored one (with `--check`).
+    Pass,
+    /// The answer differs from the stored one, or there is none anymore (with `--check`).
+    Fail,
+    /// The part produced an answer, but there is no stored one (with `--check`).
+    Missing,
+    /// The part panicked.
+    Panic,
+    /// The part didn't finish within the timeout.
+    Timeout,
+}
+
+impl Status {
+    /// Whether this status should make the run fail.
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Status::Fail | Status::Error | Status::Panic | Status::Timeout
+        )
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Status::Solved => "solved",
+            Status::Unsolved => "unsolved",
+            Status::Error => "error",
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Missing => "missing",
+            Status::Panic => "panic",
+            Status::Timeout => "timeout",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The outcome of running a single part.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Record {
+    pub year: usize,
+    pub day: usize,
+    pub part: usize,
+    pub answer: Option<String>,
+    /// The (median) time the part took.
+    pub seconds: f64,
+    /// The (median) time the day took to parse the input shared by its parts, for
+    /// days with a parse phase of their own (not included in `seconds`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parse_seconds: Option<f64>,
+    pub status: Status,
+    /// The stored answer, if the answer didn't match it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// What went wrong, if the part failed, panicked or timed out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// The timing statistics, in bench mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<Stats>,
+    /// Whether other days were running at the same time, skewing the timing.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub contended: bool,
+}
+
+impl Record {
+    fn name(&self) -> String {
+        format!("{} day {} part {}", self.year, self.day, self.part)
+    }
+}
+
+/// Writes the records in the given (non-text) format.
+pub fn write(format: Format, records: &[Record], out: &mut dyn Write) -> io::Result<()> {
+    match format {
+        Format::Text => unreachable!("text output is printed while running"),
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, records)?;
+            writeln!(out)
+        }
+        Format::Jsonl => {
+            for record in records {
+                serde_json::to_writer(&mut *out, record)?;
+                writeln!(out)?;
+            }
+
+            Ok(())
+        }
+        Format::Csv => write_csv(records, out),
+        Format::Tap => write_tap(records, out),
+        Format::Junit => write_junit(records, out),
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_csv(records: &[Record], out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "year,day,part,answer,seconds,status,parse_seconds")?;
+
+    for record in records {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            record.year,
+            record.day,
+            record.part,
+            csv_field(record.answer.as_deref().unwrap_or("")),
+            record.seconds,
+            record.status,
+            record
+                .parse_seconds
+                .map_or(String::new(), |s| s.to_string()),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_tap(records: &[Record], out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "TAP version 13")?;
+    writeln!(out, "1..{}", records.len())?;
+
+    for (i, record) in records.iter().enumerate() {
+        let ok = if record.status.is_failure() || record.status == Status::Unsolved {
+            "not ok"
+        } else {
+            "ok"
+        };
+
+        // multi-line (ASCII art) answers go into the diagnostics instead
+        let answer = match &record.answer {
+            Some(answer) if !answer.trim().contains('\n') => format!(": {}", answer.trim()),
+            _ => String::new(),
+        };
+
+        let directive = if record.status == Status::Unsolved {
+            " # TODO unsolved"
+        } else {
+            ""
+        };
+
+        writeln!(
+            out,
+            "{} {} - {}{}{}",
+            ok,
+            i + 1,
+            record.name(),
+            answer,
+            directive
+        )?;
+
+        writeln!(out, "  ---")?;
+        writeln!(out, "  status: {}", record.status)?;
+        writeln!(out, "  seconds: {}", record.seconds)?;
+
+        if let Some(parse_seconds) = record.parse_seconds {
+            writeln!(out, "  parse_seconds: {}", parse_seconds)?;
+        }
+
+        if let Some(message) = &record.message {
+            writeln!(
+                out,
+                "  message: {}",
+                serde_json::to_string(message).unwrap()
+            )?;
+        }
+
+        if let Some(expected) = &record.expected {
+            writeln!(
+                out,
+                "  expected: {}",
+                serde_json::to_string(expected).unwrap()
+            )?;
+            writeln!(
+                out,
+                "  got: {}",
+                serde_json::to_string(&record.answer).unwrap()
+            )?;
+        } else if answer.is_empty() && record.answer.is_some() {
+            writeln!(
+                out,
+                "  answer: {}",
+                serde_json::to_string(&record.answer).unwrap()
+            )?;
+        }
+
+        writeln!(out, "  ...")?;
+    }
+
+    Ok(())
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_junit(records: &[Record], out: &mut dyn Write) -> io::Result<()> {
+    // JUnit counts wrong answers as failures, but errors, panics and timeouts as errors
+    let counts = |records: &[&Record]| {
+        let count = |f: fn(Status) -> bool| records.iter().filter(|r| f(r.status)).count();
+
+        format!(
+            r#"tests="{}" failures="{}" errors="{}" skipped="{}" time="{}""#,
+            records.len(),
+            count(|s| s == Status::Fail),
+            count(|s| matches!(s, Status::Error | Status::Panic | Status::Timeout)),
+            count(|s| s == Status::Unsolved),
+            records.iter().map(|r| r.seconds).sum::<f64>(),
+        )
+    };
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites name="aoc" {}>"#,
+        counts(&records.iter().collect::<Vec<_>>())
+    )?;
+
+    let mut years: Vec<usize> = records.iter().map(|r| r.year).collect();
+    years.dedup();
+
+    for year in years {
+        let suite: Vec<&Record> = records.iter().filter(|r| r.year == year).collect();
+
+        writeln!(out, r#"  <testsuite name="{}" {}>"#, year, counts(&suite))?;
+
+        for record in suite {
+            write!(
+                out,
+                r#"    <testcase classname="y{}.d{}" name="part{}" time="{}""#,
+                record.year % 100,
+                record.day,
+                record.part,
+                record.seconds,
+            )?;
+
+            match record.status {
+                Status::Unsolved => writeln!(out, ">\n      <skipped/>\n    </testcase>")?,
+                Status::Error | Status::Panic | Status::Timeout => writeln!(
+                    out,
+                    ">\n      <error message=\"{}\">{}</error>\n    </testcase>",
+                    record.status,
+                    xml_escape(record.message.as_deref().unwrap_or("")),
+                )?,
+                Status::Fail => writeln!(
+                    out,
+                    ">\n      <failure message=\"{}\">expected: {}\ngot: {}</failure>\n    </testcase>",
+                    record.status,
+                    xml_escape(record.expected.as_deref().unwrap_or("")),
+                    xml_escape(record.answer.as_deref().unwrap_or("")),
+                )?,
+                _ => writeln!(
+                    out,
+                    ">\n      <system-out>{}</system-out>\n    </testcase>",
+                    xml_escape(record.answer.as_deref().unwrap_or(""))
+                )?,
+            }
+        }
+
+        writeln!(out, "  </testsuite>")?;
+    }
+
+    writeln!(out, "</testsuites>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(part: usize, answer: Option<&str>, status: Status) -> Record {
+        Record {
+            year: 2024,
+            day: 1,
+            part,
+            answer: answer.map(|a| a.to_string()),
+            seconds: 0.5,
+            parse_seconds: None,
+            status,
+            expected: None,
+            message: None,
+            stats: None,
+            contended: false,
+        }
+    }
+
+    fn written(format: Format, records: &[Record]) -> String {
+        let mut out = Vec::new();
+        write(format, records, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn csv_quotes_fields() {
+        let records = [
+            record(1, Some("1,2"), Status::Solved),
+            record(2, None, Status::Unsolved),
+        ];
+
+        assert_eq!(
+            written(Format::Csv, &records),
+            "year,day,part,answer,seconds,status,parse_seconds\n\
+             2024,1,1,\"1,2\",0.5,solved,\n\
+             2024,1,2,,0.5,unsolved,\n"
+        );
+    }
+
+    #[test]
+    fn jsonl_has_named_fields() {
+        let records = [record(1, Some("42"), Status::Solved)];
+
+        assert_eq!(
+            written(Format::Jsonl, &records),
+            "{\"year\":2024,\"day\":1,\"part\":1,\"answer\":\"42\",\"seconds\":0.5,\"status\":\"solved\"}\n"
+        );
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let records = vec![record(1, Some("42"), Status::Pass)];
+        let parsed: Vec<Record> = serde_json::from_str(&written(Format::Json, &records)).unwrap();
+
+        assert_eq!(parsed, records);
+    }
+
+    #[test]
+    fn tap_marks_unsolved_as_todo() {
+        let tap = written(Format::Tap, &[record(2, None, Status::Unsolved)]);
+
+        assert!(tap.contains("1..1\n"));
+        assert!(tap.contains("not ok 1 - 2024 day 1 part 2 # TODO unsolved\n"));
+    }
+}
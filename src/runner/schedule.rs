@@ -0,0 +1,89 @@
+//! Running days concurrently while still handling their results in order.
+
+use rayon::ThreadPoolBuilder;
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+use std::thread;
+
+/// Runs `run` on every job using `threads` worker threads, calling `handle` on the
+/// results in the order of the jobs (each as soon as all the earlier ones are handled).
+/// With a single thread, everything runs in order on the current thread.
+pub fn run_ordered<J: Sync, R: Send>(
+    jobs: &[J],
+    threads: usize,
+    run: impl Fn(&J) -> R + Sync,
+    mut handle: impl FnMut(&J, R),
+) {
+    if threads <= 1 {
+        for job in jobs {
+            handle(job, run(job));
+        }
+
+        return;
+    }
+
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("Failed to build the thread pool");
+
+    let (sender, receiver) = mpsc::channel();
+    let run = &run;
+
+    thread::scope(|scope| {
+        // the workers are fed from another thread, so that this one can handle results
+        scope.spawn(move || {
+            pool.scope_fifo(|s| {
+                for (i, job) in jobs.iter().enumerate() {
+                    let sender = sender.clone();
+
+                    s.spawn_fifo(move |_| {
+                        let _ = sender.send((i, run(job)));
+                    });
+                }
+            });
+        });
+
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+
+        for (i, result) in receiver {
+            pending.insert(i, result);
+
+            while let Some(result) = pending.remove(&next) {
+                handle(&jobs[next], result);
+                next += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_are_handled_in_order() {
+        let jobs: Vec<u64> = (0..50).collect();
+
+        for threads in [1, 4] {
+            let mut handled = Vec::new();
+
+            // later jobs finish first
+            run_ordered(
+                &jobs,
+                threads,
+                |&job| {
+                    thread::sleep(std::time::Duration::from_micros(50 * (50 - job)));
+                    job * 2
+                },
+                |&job, result| handled.push((job, result)),
+            );
+
+            assert_eq!(
+                handled,
+                jobs.iter().map(|&j| (j, j * 2)).collect::<Vec<_>>()
+            );
+        }
+    }
+}
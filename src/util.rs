@@ -1,3 +1,14 @@
+pub mod cycle;
+pub mod grid;
+pub mod intervals;
+pub mod math;
+pub mod ocr;
+pub mod search;
+pub mod vm;
+
+use std::any::Any;
+use std::fmt;
+
 pub trait Day {
     fn solve_part1(&self, _: &str) -> Option<String> {
         None
@@ -12,3 +23,284 @@ pub trait Day {
     }
 }
 
+/// The answer to a part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Str(String),
+    /// A multi-line picture, usually of letters drawn with `#`s.
+    Art(String),
+    /// The letters read off a picture, which is kept in `art`.
+    Letters {
+        text: String,
+        art: String,
+    },
+}
+
+impl Answer {
+    /// Classifies a textual answer: multi-line ones are art, numeric ones integers
+    /// (unless they wouldn't print the same, like `0110`).
+    pub fn from_text(text: &str) -> Answer {
+        if text.trim_matches('\n').contains('\n') {
+            return Answer::Art(text.to_string());
+        }
+
+        match text.parse::<i128>() {
+            Ok(n) if n.to_string() == text => Answer::Int(n),
+            _ => Answer::Str(text.to_string()),
+        }
+    }
+
+    /// An answer drawn as letters: the letters if they can be read (see [`ocr`]),
+    /// or else just the picture.
+    pub fn from_art(art: impl Into<String>) -> Answer {
+        let art = art.into();
+
+        match ocr::read_art(&art) {
+            Some(text) => Answer::Letters { text, art },
+            None => Answer::Art(art),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Str(s) | Answer::Art(s) => write!(f, "{}", s),
+            Answer::Letters { text, .. } => write!(f, "{}", text),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Answer {
+                    Answer::Int(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Str(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::Str(s.to_string())
+    }
+}
+
+/// Why a part produced no answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The part hasn't been solved (yet).
+    NotImplemented,
+    /// The input couldn't be parsed; `line` and `column` are 1-based.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input has no answer (e.g. a search came up empty).
+    Unsolvable(String),
+    /// Anything else that went wrong.
+    Other(String),
+}
+
+impl Error {
+    /// A parse error at the given byte offset into the input.
+    pub fn parse_at(input: &str, offset: usize, message: impl Into<String>) -> Error {
+        let before = &input[..offset.min(input.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Error::Parse {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+
+    /// A parse error at the start of `part`, which must be a slice of `input`.
+    pub fn parse_in(input: &str, part: &str, message: impl Into<String>) -> Error {
+        let offset = (part.as_ptr() as usize).saturating_sub(input.as_ptr() as usize);
+
+        Error::parse_at(input, offset, message)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NotImplemented => write!(f, "not implemented"),
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "parse error at {}:{}: {}", line, column, message),
+            Error::Unsolvable(message) => write!(f, "unsolvable: {}", message),
+            Error::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type PartResult = Result<Answer, Error>;
+
+/// A day with typed answers and errors. Days implement this, [`Day`] (which is
+/// adapted to it) or [`ParsedSolution`].
+pub trait Solution {
+    fn part1(&self, _: &str) -> PartResult {
+        Err(Error::NotImplemented)
+    }
+
+    fn part2(&self, _: &str) -> PartResult {
+        Err(Error::NotImplemented)
+    }
+
+    fn part3(&self, _: &str) -> PartResult {
+        Err(Error::NotImplemented)
+    }
+}
+
+impl<T: Day> Solution for T {
+    fn part1(&self, input: &str) -> PartResult {
+        self.solve_part1(input)
+            .map(|answer| Answer::from_text(&answer))
+            .ok_or(Error::NotImplemented)
+    }
+
+    fn part2(&self, input: &str) -> PartResult {
+        self.solve_part2(input)
+            .map(|answer| Answer::from_text(&answer))
+            .ok_or(Error::NotImplemented)
+    }
+
+    fn part3(&self, input: &str) -> PartResult {
+        self.solve_part3(input)
+            .map(|answer| Answer::from_text(&answer))
+            .ok_or(Error::NotImplemented)
+    }
+}
+
+/// A day that parses its input once, so that its parts share the parsed input
+/// (and are timed without the parsing).
+pub trait ParsedSolution {
+    type Parsed: Send + Sync + 'static;
+
+    /// Whether [`parse`](Self::parse) does any work; only unset for days that parse
+    /// within each part, so that the runner doesn't report a parse time for them.
+    const PARSES: bool = true;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error>;
+
+    fn part1(&self, _: &Self::Parsed) -> PartResult {
+        Err(Error::NotImplemented)
+    }
+
+    fn part2(&self, _: &Self::Parsed) -> PartResult {
+        Err(Error::NotImplemented)
+    }
+
+    fn part3(&self, _: &Self::Parsed) -> PartResult {
+        Err(Error::NotImplemented)
+    }
+}
+
+/// Days that parse within each part get the input itself.
+impl<T: Solution> ParsedSolution for T {
+    type Parsed = String;
+
+    const PARSES: bool = false;
+
+    fn parse(&self, input: &str) -> Result<String, Error> {
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, input: &String) -> PartResult {
+        Solution::part1(self, input)
+    }
+
+    fn part2(&self, input: &String) -> PartResult {
+        Solution::part2(self, input)
+    }
+
+    fn part3(&self, input: &String) -> PartResult {
+        Solution::part3(self, input)
+    }
+}
+
+/// The parsed input of some day.
+pub type AnyParsed = Box<dyn Any + Send + Sync>;
+
+/// Any day, as the runner sees it: [`ParsedSolution`] with the parsed input type erased.
+pub trait Runnable {
+    /// Whether the day has a parse phase of its own.
+    fn parses(&self) -> bool;
+
+    fn parse(&self, input: &str) -> Result<AnyParsed, Error>;
+
+    /// Solves a part, given the input parsed by this day.
+    fn solve(&self, part: usize, parsed: &AnyParsed) -> PartResult;
+
+    /// Parses the input and solves a part (which only the tests do in one go).
+    #[cfg(test)]
+    fn run(&self, part: usize, input: &str) -> PartResult {
+        self.solve(part, &self.parse(input)?)
+    }
+}
+
+impl<T: ParsedSolution> Runnable for T {
+    fn parses(&self) -> bool {
+        T::PARSES
+    }
+
+    fn parse(&self, input: &str) -> Result<AnyParsed, Error> {
+        Ok(Box::new(ParsedSolution::parse(self, input)?))
+    }
+
+    fn solve(&self, part: usize, parsed: &AnyParsed) -> PartResult {
+        let parsed = parsed
+            .downcast_ref::<T::Parsed>()
+            .expect("The input was parsed by a different day");
+
+        match part {
+            1 => self.part1(parsed),
+            2 => self.part2(parsed),
+            _ => self.part3(parsed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_text() {
+        assert_eq!(Answer::from_text("-42"), Answer::Int(-42));
+        assert_eq!(Answer::from_text("abc"), Answer::Str("abc".to_string()));
+        assert_eq!(
+            Answer::from_text("#.\n.#\n"),
+            Answer::Art("#.\n.#\n".to_string())
+        );
+    }
+
+    #[test]
+    fn keeps_leading_zeros() {
+        let answer = Answer::from_text("01100100101101100");
+
+        assert_eq!(answer, Answer::Str("01100100101101100".to_string()));
+        assert_eq!(answer.to_string(), "01100100101101100");
+        assert_eq!(Answer::from_text("0"), Answer::Int(0));
+    }
+}
@@ -0,0 +1,167 @@
+//! Finding where a deterministic simulation starts repeating itself, so that it can
+//! be fast-forwarded by a huge number of steps.
+//!
+//! A simulation is a starting state and a step function. Its states eventually
+//! repeat with some [`Cycle`]: after `start` steps, every `period` steps bring it
+//! back to the same state.
+//!
+//! [`brent`] uses constant memory, but computes every step several times;
+//! [`detect`] and [`state_after`] remember every state instead.
+
+use rustc_hash::FxHashMap;
+use std::hash::Hash;
+
+/// Where the states of a simulation start repeating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The number of steps before the first state that repeats (the prefix length).
+    pub start: usize,
+    /// The number of steps after which the states repeat.
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The smallest number of steps giving the same state as `n` steps.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Brent's algorithm, keeping only two states at a time.
+pub fn brent<S: PartialEq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // find the period by looking ahead from checkpoints at increasing powers of two
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // then walk two states a period apart until they meet at the start
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial;
+
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// Finds the cycle by remembering when each state was seen, which computes every
+/// step only once.
+pub fn detect<S: Hash + Eq + Clone>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut seen: FxHashMap<S, usize> = FxHashMap::default();
+    let mut state = initial;
+
+    for i in 0.. {
+        if let Some(&start) = seen.get(&state) {
+            return Cycle {
+                start,
+                period: i - start,
+            };
+        }
+
+        let next = step(&state);
+        seen.insert(state, i);
+        state = next;
+    }
+
+    unreachable!()
+}
+
+/// The state after `n` steps, skipping over the repetitions once the states start
+/// repeating.
+pub fn state_after<S: Hash + Eq + Clone>(initial: S, n: usize, mut step: impl FnMut(&S) -> S) -> S {
+    let mut seen: FxHashMap<S, usize> = FxHashMap::default();
+    let mut states: Vec<S> = Vec::new();
+    let mut state = initial;
+
+    for i in 0..n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                period: i - start,
+            };
+
+            return states.swap_remove(cycle.reduce(n));
+        }
+
+        let next = step(&state);
+        seen.insert(state.clone(), i);
+        states.push(state);
+        state = next;
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0, 1, ..., 9 and then 3, 4, ..., 9 over and over.
+    fn step(&x: &u32) -> u32 {
+        if x == 9 {
+            3
+        } else {
+            x + 1
+        }
+    }
+
+    const CYCLE: Cycle = Cycle {
+        start: 3,
+        period: 7,
+    };
+
+    #[test]
+    fn detectors_agree() {
+        assert_eq!(brent(0, step), CYCLE);
+        assert_eq!(detect(0, step), CYCLE);
+
+        // a pure cycle starts right away
+        let pure = Cycle {
+            start: 0,
+            period: 7,
+        };
+
+        assert_eq!(brent(5, step), pure);
+        assert_eq!(detect(5, step), pure);
+    }
+
+    #[test]
+    fn reduces_step_counts() {
+        assert_eq!(CYCLE.reduce(2), 2);
+        assert_eq!(CYCLE.reduce(10), 3);
+        assert_eq!(CYCLE.reduce(1_000_000_000), 3 + (1_000_000_000 - 3) % 7);
+    }
+
+    #[test]
+    fn fast_forwards() {
+        for n in [0, 2, 9, 10, 11, 1_000_000_000] {
+            let expected = (0..CYCLE.reduce(n)).fold(0, |x, _| step(&x));
+
+            assert_eq!(state_after(0, n, step), expected, "after {} steps", n);
+        }
+    }
+}
@@ -0,0 +1,366 @@
+//! A rectangular 2D grid, as most map-like puzzle inputs are.
+//!
+//! Positions are `(x, y)` pairs, with `x` going right and `y` going down from the
+//! top-left corner.
+
+use crate::util::Error;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+pub type Point = (usize, usize);
+
+/// The offsets to the 4 orthogonal neighbours: up, right, down and left.
+pub const DIRECTIONS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// The offsets to all 8 neighbours, clockwise from up.
+pub const DIRECTIONS_8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    /// The cells, row by row.
+    cells: Vec<T>,
+}
+
+impl Grid<char> {
+    /// Parses a grid of characters, one row per line.
+    pub fn parse(input: &str) -> Result<Grid<char>, Error> {
+        Grid::parse_with(input, Some)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid filled with copies of `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Creates a grid from its rows, which must all be of the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let width = rows.first().map_or(0, |row| row.len());
+
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        Some(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses a grid, one row per line, converting each character with `f`.
+    /// Surrounding blank lines are ignored, but the rows must all be of the same length.
+    pub fn parse_with(input: &str, mut f: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, Error> {
+        let mut rows = Vec::new();
+
+        for line in input.trim_matches('\n').lines() {
+            let line = line.trim_end_matches('\r');
+            let mut row = Vec::new();
+
+            for (offset, c) in line.char_indices() {
+                let cell = f(c).ok_or_else(|| {
+                    Error::parse_in(input, &line[offset..], format!("unexpected '{}'", c))
+                })?;
+
+                row.push(cell);
+            }
+
+            if rows
+                .first()
+                .is_some_and(|first: &Vec<T>| first.len() != row.len())
+            {
+                return Err(Error::parse_in(input, line, "rows of different lengths"));
+            }
+
+            rows.push(row);
+        }
+
+        Ok(Grid::from_rows(rows).unwrap())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, x: isize, y: isize) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    /// The cell at `(x, y)`, or `None` outside of the grid.
+    pub fn get(&self, x: isize, y: isize) -> Option<&T> {
+        self.in_bounds(x, y)
+            .then(|| &self.cells[y as usize * self.width + x as usize])
+    }
+
+    /// The cell at `(x, y)`, with the grid repeating infinitely in every direction.
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+
+        &self[(x, y)]
+    }
+
+    /// The position `(dx, dy)` away from `point`, if it is in the grid.
+    pub fn step(&self, point: Point, (dx, dy): (isize, isize)) -> Option<Point> {
+        let (x, y) = (point.0 as isize + dx, point.1 as isize + dy);
+
+        self.in_bounds(x, y).then_some((x as usize, y as usize))
+    }
+
+    /// The orthogonal neighbours of `point` that are in the grid.
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        DIRECTIONS_4
+            .iter()
+            .filter_map(move |&direction| self.step(point, direction))
+    }
+
+    /// All (including diagonal) neighbours of `point` that are in the grid.
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        DIRECTIONS_8
+            .iter()
+            .filter_map(move |&direction| self.step(point, direction))
+    }
+
+    /// All positions, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width;
+
+        (0..self.width * self.height).map(move |i| (i % width, i / width))
+    }
+
+    /// All cells with their positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a zero size, but then there are no cells anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// The position of the first cell (row by row) equal to `value`.
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .find(|(_, cell)| *cell == value)
+            .map(|(point, _)| point)
+    }
+
+    /// The positions of all cells equal to `value`.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Point> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(point, _)| point)
+    }
+
+    /// Builds a grid of the given size from the cell each position should be taken from.
+    fn remap(&self, width: usize, height: usize, from: impl Fn(usize, usize) -> Point) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..width * height)
+            .map(|i| self[from(i % width, i / width)].clone())
+            .collect();
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Swaps the rows and the columns.
+    pub fn transposed(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+
+    /// Rotates the grid by 90 degrees clockwise.
+    pub fn rotated_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let height = self.height;
+
+        self.remap(self.height, self.width, move |x, y| (y, height - 1 - x))
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn flipped_horizontally(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let width = self.width;
+
+        self.remap(self.width, self.height, move |x, y| (width - 1 - x, y))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): Point) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is out of the grid",
+            x,
+            y
+        );
+
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, (x, y): Point) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) is out of the grid",
+            x,
+            y
+        );
+
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// Prints the grid row by row, with the cells of a row next to each other.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y != 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse("abc\ndef\n").unwrap()
+    }
+
+    #[test]
+    fn parses_and_prints() {
+        let grid = grid();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.to_string(), "abc\ndef");
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        assert_eq!(
+            Grid::parse("abc\nde"),
+            Err(Error::Parse {
+                line: 2,
+                column: 1,
+                message: "rows of different lengths".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn indexes_checked_and_wrapping() {
+        let grid = grid();
+
+        assert_eq!(grid.get(-1, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(grid.get(1, 1), Some(&'e'));
+        assert_eq!(grid.get_wrapping(-1, 3), &'f');
+    }
+
+    #[test]
+    fn neighbours_stay_in_bounds() {
+        let grid = grid();
+
+        assert_eq!(
+            grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours8((0, 0)).count(), 3);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = grid();
+
+        assert_eq!(grid.row(1), ['d', 'e', 'f']);
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.transposed().to_string(), "ad\nbe\ncf");
+    }
+
+    #[test]
+    fn rotates_and_flips() {
+        let grid = grid();
+
+        assert_eq!(grid.rotated_clockwise().to_string(), "da\neb\nfc");
+        assert_eq!(grid.flipped_horizontally().to_string(), "cba\nfed");
+        assert_eq!(
+            (0..4).fold(grid.clone(), |grid, _| grid.rotated_clockwise()),
+            grid
+        );
+    }
+
+    #[test]
+    fn finds_values() {
+        let grid = grid();
+
+        assert_eq!(grid.find(&'e'), Some((1, 1)));
+        assert_eq!(grid.find(&'z'), None);
+    }
+}
@@ -0,0 +1,354 @@
+//! Sets of integers stored as ranges, for when the numbers are too many to list:
+//! [`RangeSet`] in one dimension and [`BoxSet`] (of [`Cuboid`]s) in any number.
+//!
+//! Ranges are half-open like `a..b`, so an inclusive `a..=b` from the input is
+//! `a..b + 1`.
+
+use std::ops::Range;
+
+/// A set of integers, kept as sorted, disjoint and non-adjacent ranges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeSet {
+    ranges: Vec<Range<i64>>,
+}
+
+impl RangeSet {
+    pub fn new() -> RangeSet {
+        RangeSet::default()
+    }
+
+    /// The ranges of the set, sorted and merged.
+    pub fn ranges(&self) -> &[Range<i64>] {
+        &self.ranges
+    }
+
+    /// The number of integers in the set.
+    pub fn len(&self) -> i64 {
+        self.ranges
+            .iter()
+            .map(|range| range.end - range.start)
+            .sum()
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        // the first range ending after the value is the only one that can contain it
+        let i = self.ranges.partition_point(|range| range.end <= value);
+
+        self.ranges.get(i).is_some_and(|range| range.start <= value)
+    }
+
+    /// Adds a range to the set, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+
+        let merged = match self.ranges.get(first..last) {
+            Some([]) | None => range,
+            Some(overlapping) => {
+                overlapping[0].start.min(range.start)
+                    ..overlapping[overlapping.len() - 1].end.max(range.end)
+            }
+        };
+
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Removes a range from the set, splitting the ranges it cuts through.
+    pub fn remove(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+
+        let first = self.ranges.partition_point(|r| r.end <= range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+
+        let mut remaining = Vec::new();
+
+        if let Some(r) = self.ranges.get(first).filter(|_| first < last) {
+            if r.start < range.start {
+                remaining.push(r.start..range.start);
+            }
+        }
+
+        if let Some(r) = self
+            .ranges
+            .get(last.wrapping_sub(1))
+            .filter(|_| first < last)
+        {
+            if range.end < r.end {
+                remaining.push(range.end..r.end);
+            }
+        }
+
+        self.ranges.splice(first..last, remaining);
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        let mut union = self.clone();
+
+        for range in &other.ranges {
+            union.insert(range.clone());
+        }
+
+        union
+    }
+
+    pub fn difference(&self, other: &RangeSet) -> RangeSet {
+        let mut difference = self.clone();
+
+        for range in &other.ranges {
+            difference.remove(range.clone());
+        }
+
+        difference
+    }
+
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        // walk both sorted lists at once, like in a merge
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            let overlap = a.start.max(b.start)..a.end.min(b.end);
+
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        RangeSet { ranges }
+    }
+
+    /// Everything within `universe` that isn't in the set.
+    pub fn complement(&self, universe: Range<i64>) -> RangeSet {
+        let mut complement = RangeSet::from_iter([universe]);
+
+        for range in &self.ranges {
+            complement.remove(range.clone());
+        }
+
+        complement
+    }
+
+    /// Splits the set by a list of (source range, offset) rules: the parts covered by
+    /// a rule are moved by its offset, while the rest stays where it is. Where rules
+    /// overlap, the first one applies.
+    pub fn map_through(&self, rules: &[(Range<i64>, i64)]) -> RangeSet {
+        let mut unmapped = self.clone();
+        let mut mapped = RangeSet::new();
+
+        for (source, offset) in rules {
+            let covered = unmapped.intersection(&RangeSet::from_iter([source.clone()]));
+
+            for range in covered.ranges() {
+                mapped.insert(range.start + offset..range.end + offset);
+            }
+
+            unmapped = unmapped.difference(&covered);
+        }
+
+        mapped.union(&unmapped)
+    }
+}
+
+impl FromIterator<Range<i64>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(ranges: I) -> RangeSet {
+        let mut set = RangeSet::new();
+
+        for range in ranges {
+            set.insert(range);
+        }
+
+        set
+    }
+}
+
+/// An axis-aligned box in `N` dimensions, spanning `min[i]..max[i]` along axis `i`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid<const N: usize> {
+    pub min: [i64; N],
+    pub max: [i64; N],
+}
+
+impl<const N: usize> Cuboid<N> {
+    pub fn new(min: [i64; N], max: [i64; N]) -> Cuboid<N> {
+        Cuboid { min, max }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|i| self.min[i] >= self.max[i])
+    }
+
+    /// The number of integer points in the box.
+    pub fn volume(&self) -> i128 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        (0..N)
+            .map(|i| (self.max[i] - self.min[i]) as i128)
+            .product()
+    }
+
+    pub fn intersection(&self, other: &Cuboid<N>) -> Option<Cuboid<N>> {
+        let cuboid = Cuboid {
+            min: std::array::from_fn(|i| self.min[i].max(other.min[i])),
+            max: std::array::from_fn(|i| self.max[i].min(other.max[i])),
+        };
+
+        (!cuboid.is_empty()).then_some(cuboid)
+    }
+
+    /// The parts of this box outside of `other`, as at most `2 * N` disjoint boxes.
+    pub fn subtract(&self, other: &Cuboid<N>) -> Vec<Cuboid<N>> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+
+        let mut pieces = Vec::new();
+        let mut rest = *self;
+
+        // slice off the parts below and above the overlap, one axis at a time
+        for i in 0..N {
+            if rest.min[i] < overlap.min[i] {
+                let mut below = rest;
+                below.max[i] = overlap.min[i];
+                pieces.push(below);
+                rest.min[i] = overlap.min[i];
+            }
+
+            if overlap.max[i] < rest.max[i] {
+                let mut above = rest;
+                above.min[i] = overlap.max[i];
+                pieces.push(above);
+                rest.max[i] = overlap.max[i];
+            }
+        }
+
+        pieces
+    }
+}
+
+/// A set of points in `N` dimensions, kept as disjoint boxes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoxSet<const N: usize> {
+    cuboids: Vec<Cuboid<N>>,
+}
+
+impl<const N: usize> Default for BoxSet<N> {
+    fn default() -> BoxSet<N> {
+        BoxSet {
+            cuboids: Vec::new(),
+        }
+    }
+}
+
+impl<const N: usize> BoxSet<N> {
+    pub fn new() -> BoxSet<N> {
+        BoxSet::default()
+    }
+
+    pub fn insert(&mut self, cuboid: Cuboid<N>) {
+        if cuboid.is_empty() {
+            return;
+        }
+
+        self.remove(&cuboid);
+        self.cuboids.push(cuboid);
+    }
+
+    pub fn remove(&mut self, cuboid: &Cuboid<N>) {
+        self.cuboids = self
+            .cuboids
+            .iter()
+            .flat_map(|existing| existing.subtract(cuboid))
+            .collect();
+    }
+
+    /// The exact number of integer points in the set.
+    pub fn volume(&self) -> i128 {
+        self.cuboids.iter().map(Cuboid::volume).sum()
+    }
+}
+
+#[cfg(test)]
+// `[a..b]` is meant as a list of one range here
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_merge_and_removes_split() {
+        let mut set = RangeSet::from_iter([0..5, 10..15]);
+
+        set.insert(5..7);
+        assert_eq!(set.ranges(), [0..7, 10..15]);
+
+        set.insert(6..12);
+        assert_eq!(set.ranges(), [0..15]);
+
+        set.remove(3..5);
+        set.remove(14..20);
+        assert_eq!(set.ranges(), [0..3, 5..14]);
+        assert_eq!(set.len(), 12);
+
+        assert!(set.contains(0) && set.contains(13));
+        assert!(!set.contains(3) && !set.contains(14) && !set.contains(-1));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = RangeSet::from_iter([0..10, 20..30]);
+        let b = RangeSet::from_iter([5..25]);
+
+        assert_eq!(a.union(&b).ranges(), [0..30]);
+        assert_eq!(a.intersection(&b).ranges(), [5..10, 20..25]);
+        assert_eq!(a.difference(&b).ranges(), [0..5, 25..30]);
+        assert_eq!(a.complement(-5..35).ranges(), [-5..0, 10..20, 30..35]);
+    }
+
+    #[test]
+    fn maps_through_rules() {
+        let set = RangeSet::from_iter([0..10]);
+
+        // 2..4 moves up by 100, 3..6 down by 50 (but 3 was already moved)
+        let mapped = set.map_through(&[(2..4, 100), (3..6, -50)]);
+
+        assert_eq!(mapped.ranges(), [-46..-44, 0..2, 6..10, 102..104]);
+        assert_eq!(mapped.len(), set.len());
+    }
+
+    #[test]
+    fn cuboids_subtract_into_disjoint_pieces() {
+        let a = Cuboid::new([0, 0, 0], [3, 3, 3]);
+        let b = Cuboid::new([1, 1, 1], [2, 2, 2]);
+
+        let pieces = a.subtract(&b);
+        assert_eq!(pieces.len(), 6);
+        assert_eq!(pieces.iter().map(Cuboid::volume).sum::<i128>(), 26);
+        assert_eq!(b.subtract(&a), []);
+    }
+
+    #[test]
+    fn box_sets_count_exactly() {
+        let mut set = BoxSet::new();
+
+        set.insert(Cuboid::new([0, 0], [10, 10]));
+        set.insert(Cuboid::new([5, 5], [15, 15]));
+        assert_eq!(set.volume(), 175);
+
+        set.remove(&Cuboid::new([0, 0], [15, 1]));
+        assert_eq!(set.volume(), 165);
+    }
+}
@@ -0,0 +1,264 @@
+//! Number theory: greatest common divisors, divisors, modular arithmetic, the
+//! Chinese remainder theorem and affine maps modulo some number.
+//!
+//! Everything works on `i64`, with intermediate products done in `i128` so that
+//! moduli up to `i64::MAX` don't overflow. Modular results are always in `0..m`.
+
+/// The greatest common divisor, which is never negative (and 0 only for `gcd(0, 0)`).
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a as i64
+}
+
+/// The least common multiple, or `None` if it doesn't fit into an `i64`.
+pub fn checked_lcm(a: i64, b: i64) -> Option<i64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+
+    (a / gcd(a, b)).checked_mul(b).map(i64::abs)
+}
+
+/// The least common multiple (never negative).
+pub fn lcm(a: i64, b: i64) -> i64 {
+    checked_lcm(a, b).unwrap_or_else(|| panic!("lcm({}, {}) overflows", a, b))
+}
+
+/// The extended Euclidean algorithm: returns `(g, x, y)` such that
+/// `a * x + b * y == g`, where `g` is `gcd(a, b)`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a as i128, b as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    let (mut old_t, mut t) = (0i128, 1i128);
+
+    while r != 0 {
+        let quotient = old_r / r;
+
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+        (old_t, t) = (t, old_t - quotient * t);
+    }
+
+    if old_r < 0 {
+        (old_r, old_s, old_t) = (-old_r, -old_s, -old_t);
+    }
+
+    (old_r as i64, old_s as i64, old_t as i64)
+}
+
+/// `a` modulo `m`, in `0..m` even for a negative `a`.
+pub fn modulo(a: i64, m: i64) -> i64 {
+    a.rem_euclid(m)
+}
+
+/// `a * b` modulo `m`, without overflowing.
+pub fn mul_mod(a: i64, b: i64, m: i64) -> i64 {
+    (a as i128 * b as i128).rem_euclid(m as i128) as i64
+}
+
+/// `base` to the power of `exponent` modulo `m`, by repeated squaring.
+pub fn mod_pow(base: i64, mut exponent: u64, m: i64) -> i64 {
+    let mut result = modulo(1, m);
+    let mut base = modulo(base, m);
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+
+        base = mul_mod(base, base, m);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+/// The `x` in `0..m` with `a * x == 1` modulo `m`, if `a` and `m` are coprime.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(modulo(a, m), m);
+
+    (g == 1).then(|| modulo(x, m))
+}
+
+/// The positive divisors of a positive `n`, in increasing order, by trial division.
+pub fn divisors(n: i64) -> Vec<i64> {
+    let mut small = Vec::new();
+    let mut large = Vec::new();
+
+    for d in (1..).take_while(|d| d * d <= n) {
+        if n % d == 0 {
+            small.push(d);
+
+            if d * d != n {
+                large.push(n / d);
+            }
+        }
+    }
+
+    small.extend(large.into_iter().rev());
+    small
+}
+
+/// Solves the system of congruences `x == residue (mod modulus)`, whose moduli don't
+/// have to be coprime. Returns the smallest non-negative solution along with the
+/// modulus it is unique under (the lcm of the moduli), or `None` if the congruences
+/// contradict each other or the lcm doesn't fit into an `i64`.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let (mut x, mut m) = (0i64, 1i64);
+
+    for &(residue, modulus) in congruences {
+        // x + m * k == residue (mod modulus), so m * k == residue - x (mod modulus)
+        let (g, inverse, _) = extended_gcd(m, modulus);
+        let difference = residue as i128 - x as i128;
+
+        if difference % g as i128 != 0 {
+            return None;
+        }
+
+        let step = modulus / g;
+        let k =
+            ((difference / g as i128) % step as i128 * inverse as i128).rem_euclid(step as i128);
+        let new_m = checked_lcm(m, modulus)?;
+
+        x = (x as i128 + m as i128 * k).rem_euclid(new_m as i128) as i64;
+        m = new_m;
+    }
+
+    Some((x, m))
+}
+
+/// The map `x -> a * x + b` modulo `m`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Affine {
+    pub a: i64,
+    pub b: i64,
+    pub m: i64,
+}
+
+impl Affine {
+    pub fn new(a: i64, b: i64, m: i64) -> Affine {
+        Affine {
+            a: modulo(a, m),
+            b: modulo(b, m),
+            m,
+        }
+    }
+
+    /// The map that leaves everything as it is.
+    pub fn identity(m: i64) -> Affine {
+        Affine::new(1, 0, m)
+    }
+
+    pub fn apply(&self, x: i64) -> i64 {
+        ((mul_mod(self.a, x, self.m) as i128 + self.b as i128) % self.m as i128) as i64
+    }
+
+    /// The map that applies `self` and then `next`.
+    pub fn then(&self, next: &Affine) -> Affine {
+        debug_assert_eq!(self.m, next.m, "composing maps with different moduli");
+
+        Affine {
+            a: mul_mod(next.a, self.a, self.m),
+            b: next.apply(self.b),
+            m: self.m,
+        }
+    }
+
+    /// The map applied `times` times in a row, by repeated squaring.
+    pub fn pow(&self, mut times: u64) -> Affine {
+        let mut result = Affine::identity(self.m);
+        let mut power = *self;
+
+        while times > 0 {
+            if times & 1 == 1 {
+                result = result.then(&power);
+            }
+
+            power = power.then(&power);
+            times >>= 1;
+        }
+
+        result
+    }
+
+    /// The map undoing this one, if `a` is invertible modulo `m`.
+    pub fn inverse(&self) -> Option<Affine> {
+        let a = mod_inverse(self.a, self.m)?;
+
+        Some(Affine::new(a, -mul_mod(a, self.b, self.m), self.m))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, -18), 6);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6), 12);
+        assert_eq!(checked_lcm(i64::MAX, i64::MAX - 1), None);
+        assert_eq!(lcm(1 << 40, 3 << 20), 3 << 40);
+    }
+
+    #[test]
+    fn extended_gcd_gives_bezout_coefficients() {
+        for (a, b) in [(240, 46), (-7, 3), (0, 5), (17, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        let m = 1_000_000_000_000_007;
+
+        assert_eq!(modulo(-3, 5), 2);
+        assert_eq!(mul_mod(m - 1, m - 1, m), 1);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(5, 0, 1), 0);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(6, 9), None);
+
+        let inverse = mod_inverse(123_456_789, m).unwrap();
+        assert_eq!(mul_mod(inverse, 123_456_789, m), 1);
+    }
+
+    #[test]
+    fn divisors_in_order() {
+        assert_eq!(divisors(1), [1]);
+        assert_eq!(divisors(36), [1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        assert_eq!(divisors(97), [1, 97]);
+        assert!(divisors(0).is_empty());
+    }
+
+    #[test]
+    fn crt_with_and_without_coprime_moduli() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn affine_maps_compose() {
+        let m = 10007;
+        let f = Affine::new(3, 4, m);
+        let g = Affine::new(-2, 7, m);
+
+        assert_eq!(f.then(&g).apply(5), g.apply(f.apply(5)));
+        assert_eq!(f.pow(3).apply(5), f.apply(f.apply(f.apply(5))));
+        assert_eq!(f.pow(0), Affine::identity(m));
+        assert_eq!(f.inverse().unwrap().apply(f.apply(1234)), 1234);
+        assert_eq!(f.then(&f.inverse().unwrap()), Affine::identity(m));
+    }
+}
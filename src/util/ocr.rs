@@ -0,0 +1,260 @@
+//! Reading the capital letters that some puzzles draw as their answer.
+//!
+//! There are three fonts: a small one, 6 pixels high and usually 4 wide, the
+//! whole alphabet of the 2016 display, which is as high but draws some letters
+//! differently, and a large one, 10 pixels high and 6 wide, which was used in 2018.
+//! Only the letters of the small and large fonts that have shown up in answers so
+//! far are known.
+
+use crate::util::grid::Grid;
+
+#[rustfmt::skip]
+const SMALL_FONT: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// The letters of the display from 2016 day 8.
+#[rustfmt::skip]
+const DISPLAY_FONT: [(char, [&str; 6]); 26] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('D', ["###.", "#..#", "#..#", "#..#", "#..#", "###."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["#####", "..#..", "..#..", "..#..", "..#..", "#####"]),
+    ('J', [".###", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#..#", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('M', ["#..#", "####", "#..#", "#..#", "#..#", "#..#"]),
+    ('N', ["#..#", "##.#", "#.##", "#..#", "#..#", "#..#"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('Q', [".##.", "#..#", "#..#", "#..#", "#.#.", ".#.#"]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#..#", "#..#"]),
+    ('S', [".###", "#...", ".##.", "...#", "...#", "###."]),
+    ('T', ["####", ".#..", ".#..", ".#..", ".#..", ".#.."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('V', ["#..#", "#..#", "#..#", "#..#", ".#.#", "..#."]),
+    ('W', ["#..#", "#..#", "#..#", "#..#", "####", "#..#"]),
+    ('X', ["#..#", "#..#", ".##.", "#..#", "#..#", "#..#"]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "..#.", ".#..", ".#..", "#...", "####"]),
+];
+
+#[rustfmt::skip]
+const LARGE_FONT: [(char, [&str; 10]); 15] = [
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Reads the letters drawn by the `true` cells of the grid, ignoring the empty
+/// rows and columns around them. Returns `None` if the drawing isn't in one of the
+/// fonts or has a letter that isn't known.
+pub fn read(grid: &Grid<bool>) -> Option<String> {
+    let lit_rows: Vec<usize> = (0..grid.height())
+        .filter(|&y| grid.row(y).contains(&true))
+        .collect();
+
+    let (&top, &bottom) = (lit_rows.first()?, lit_rows.last()?);
+
+    // the 2016 display draws some letters differently than the other small ones
+    match bottom - top + 1 {
+        6 => read_in(grid, top, &glyphs(&SMALL_FONT))
+            .or_else(|| read_in(grid, top, &glyphs(&DISPLAY_FONT))),
+        10 => read_in(grid, top, &glyphs(&LARGE_FONT)),
+        _ => None,
+    }
+}
+
+/// The glyphs of a font, whatever their height.
+fn glyphs<const N: usize>(
+    font: &'static [(char, [&'static str; N])],
+) -> Vec<(char, &'static [&'static str])> {
+    font.iter().map(|(c, rows)| (*c, &rows[..])).collect()
+}
+
+/// Reads the letters whose top row is `top`, all of which have to be in the font.
+fn read_in(grid: &Grid<bool>, top: usize, glyphs: &[(char, &[&str])]) -> Option<String> {
+    let bottom = top + glyphs.first()?.1.len() - 1;
+
+    let is_lit_column = |x: usize| (top..=bottom).any(|y| grid[(x, y)]);
+    let matches = |left: usize, rows: &[&str]| {
+        rows.iter().enumerate().all(|(dy, row)| {
+            row.bytes()
+                .enumerate()
+                .all(|(dx, pixel)| (pixel == b'#') == grid[(left + dx, top + dy)])
+        })
+    };
+
+    let mut text = String::new();
+    let mut x = 0;
+
+    while x < grid.width() {
+        if !is_lit_column(x) {
+            x += 1;
+            continue;
+        }
+
+        // letters usually have an empty column between them, but can touch (like a `Y`
+        // in 2016), so the widest letter that fits into the lit columns is taken
+        let end = (x..grid.width())
+            .find(|&x| !is_lit_column(x))
+            .unwrap_or(grid.width());
+
+        let (letter, rows) = glyphs
+            .iter()
+            .filter(|(_, rows)| x + rows[0].len() <= end && matches(x, rows))
+            .max_by_key(|(_, rows)| rows[0].len())?;
+
+        text.push(*letter);
+        x += rows[0].len();
+    }
+
+    Some(text)
+}
+
+/// Reads the letters of a multi-line drawing, in which spaces and `.`s are empty
+/// and any other character is lit.
+pub fn read_art(art: &str) -> Option<String> {
+    let rows: Vec<Vec<bool>> = art
+        .lines()
+        .map(|line| line.chars().map(|c| c != ' ' && c != '.').collect())
+        .collect();
+
+    // lines may be missing their trailing spaces
+    let width = rows.iter().map(Vec::len).max()?;
+    let rows = rows
+        .into_iter()
+        .map(|mut row| {
+            row.resize(width, false);
+            row
+        })
+        .collect();
+
+    read(&Grid::from_rows(rows)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyphs_have_no_empty_columns() {
+        let fonts = [
+            glyphs(&SMALL_FONT),
+            glyphs(&DISPLAY_FONT),
+            glyphs(&LARGE_FONT),
+        ];
+
+        for (c, rows) in fonts.into_iter().flatten() {
+            let width = rows[0].len();
+
+            assert!(rows.iter().all(|row| row.len() == width), "{} is ragged", c);
+            assert!(
+                (0..width).all(|x| rows.iter().any(|row| row.as_bytes()[x] == b'#')),
+                "{} has an empty column",
+                c
+            );
+        }
+    }
+
+    #[test]
+    fn reads_the_small_font() {
+        let art = "
+ ##  #### #  #
+#  #    # #  #
+#  #   #  ####
+####  #   #  #
+#  # #    #  #
+#  # #### #  #
+";
+
+        assert_eq!(read_art(art), Some("AZH".to_string()));
+    }
+
+    #[test]
+    fn reads_touching_letters() {
+        let art = "
+#   # ##
+#   ##  #
+ # # #  #
+  #  ####
+  #  #  #
+  #  #  #
+";
+
+        assert_eq!(read_art(art), Some("YA".to_string()));
+    }
+
+    #[test]
+    fn reads_the_display_font() {
+        let art = "
+###  #  # ##### ####
+#  # ####   #    #
+#  # #  #   #    #
+#  # #  #   #    #
+#  # #  #   #    #
+###  #  # #####  #
+";
+
+        assert_eq!(read_art(art), Some("DMIT".to_string()));
+    }
+
+    #[test]
+    fn reads_the_large_font() {
+        let art = "
+  ##    #    #
+ #  #   ##   #
+#    #  ##   #
+#    #  # #  #
+#    #  # #  #
+######  #  # #
+#    #  #  # #
+#    #  #   ##
+#    #  #   ##
+#    #  #    #
+";
+
+        assert_eq!(read_art(art), Some("AN".to_string()));
+    }
+
+    #[test]
+    fn rejects_unknown_drawings() {
+        assert_eq!(read_art("#\n#\n#"), None);
+        assert_eq!(read_art("#####\n#...#\n#...#\n#...#\n#...#\n#####"), None);
+        assert_eq!(read_art(""), None);
+    }
+}
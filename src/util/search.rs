@@ -0,0 +1,446 @@
+//! Shortest-path searches over implicit graphs, given as closures from a node to its
+//! neighbours (with the cost of getting to them, for the weighted searches).
+//!
+//! [`bfs`], [`dijkstra`] and [`dijkstra_all`] remember how every node was reached in
+//! [`Paths`], so that the paths themselves can be reconstructed. For state spaces too
+//! large to keep in memory, [`ida_star`] only ever remembers the current path.
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The cost of a path: anything that can be added up and compared, with the
+/// default value as zero.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<C: Copy + Ord + Default + Add<Output = C>> Cost for C {}
+
+/// The distances to the nodes reached by a search and how they were reached.
+#[derive(Debug, Clone)]
+pub struct Paths<N, C> {
+    distances: FxHashMap<N, C>,
+    /// The nodes each node was reached from (on a shortest path); all of them for
+    /// [`dijkstra_all`], only the first one otherwise. Start nodes have none.
+    predecessors: FxHashMap<N, Vec<N>>,
+    /// The first goal node that was reached, if any.
+    pub goal: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Cost> Paths<N, C> {
+    fn new() -> Paths<N, C> {
+        Paths {
+            distances: FxHashMap::default(),
+            predecessors: FxHashMap::default(),
+            goal: None,
+        }
+    }
+
+    /// The distance to a node, if it was reached.
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.distances.get(node).copied()
+    }
+
+    /// The distance to the goal, if one was reached.
+    pub fn goal_distance(&self) -> Option<C> {
+        self.distance(self.goal.as_ref()?)
+    }
+
+    /// All nodes that were reached, with their distances.
+    pub fn distances(&self) -> &FxHashMap<N, C> {
+        &self.distances
+    }
+
+    /// A shortest path from a start node to the given node (both included).
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.distances.get(node)?;
+
+        let mut path = vec![node.clone()];
+
+        while let Some(previous) = self
+            .predecessors
+            .get(path.last().unwrap())
+            .and_then(|predecessors| predecessors.first())
+        {
+            path.push(previous.clone());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    /// A shortest path to the goal, if one was reached.
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+
+    /// All nodes on any of the shortest paths to the given nodes (themselves included).
+    /// Only [`dijkstra_all`] (and [`bfs`] on a tree) remembers every such path.
+    pub fn on_shortest_paths<'a>(&self, nodes: impl IntoIterator<Item = &'a N>) -> FxHashSet<N>
+    where
+        N: 'a,
+    {
+        let mut seen: FxHashSet<N> = FxHashSet::default();
+        let mut stack: Vec<N> = nodes
+            .into_iter()
+            .filter(|node| self.distances.contains_key(node))
+            .cloned()
+            .collect();
+
+        while let Some(node) = stack.pop() {
+            if !seen.insert(node.clone()) {
+                continue;
+            }
+
+            for previous in self.predecessors.get(&node).into_iter().flatten() {
+                stack.push(previous.clone());
+            }
+        }
+
+        seen
+    }
+}
+
+/// Breadth-first search from `start`, stopping once a node satisfying `is_goal` is
+/// reached (pass `|_| false` to reach everything reachable).
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Paths<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths::new();
+    let mut queue = VecDeque::from([start.clone()]);
+
+    paths.distances.insert(start, 0);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            paths.goal = Some(node);
+            break;
+        }
+
+        let distance = paths.distances[&node];
+
+        for next in neighbours(&node) {
+            if paths.distances.contains_key(&next) {
+                continue;
+            }
+
+            paths.distances.insert(next.clone(), distance + 1);
+            paths.predecessors.insert(next.clone(), vec![node.clone()]);
+            queue.push_back(next);
+        }
+    }
+
+    paths
+}
+
+/// A node in the priority queue, ordered by its (estimated) cost only,
+/// with the lowest cost first.
+struct Entry<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> Ord for Entry<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority) // reverse the order to make it a min-heap
+    }
+}
+
+impl<N, C: Ord> PartialOrd for Entry<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for Entry<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Entry<N, C> {}
+
+/// The search behind [`dijkstra`], [`dijkstra_all`] and [`astar`].
+fn best_first<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+    all: bool,
+) -> Paths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut paths = Paths::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        paths.distances.insert(start.clone(), C::default());
+        heap.push(Entry {
+            priority: heuristic(&start),
+            cost: C::default(),
+            node: start,
+        });
+    }
+
+    while let Some(Entry { cost, node, .. }) = heap.pop() {
+        // skip stale entries of nodes that have since been reached more cheaply
+        if paths.distances.get(&node).is_some_and(|&best| cost > best) {
+            continue;
+        }
+
+        // when looking for all shortest paths, nodes as far as the goal are still expanded
+        if let Some(goal) = &paths.goal {
+            if !all || cost > paths.distances[goal] {
+                break;
+            }
+        } else if is_goal(&node) {
+            paths.goal = Some(node.clone());
+
+            if !all {
+                break;
+            }
+        }
+
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+
+            match paths.distances.get(&next) {
+                Some(&best) if next_cost > best => continue,
+                Some(&best) if next_cost == best => {
+                    // (start nodes have no predecessors, even when reached again)
+                    if let Some(predecessors) = paths.predecessors.get_mut(&next).filter(|_| all) {
+                        predecessors.push(node.clone());
+                    }
+
+                    continue;
+                }
+                _ => {}
+            }
+
+            paths.distances.insert(next.clone(), next_cost);
+            paths.predecessors.insert(next.clone(), vec![node.clone()]);
+
+            heap.push(Entry {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+
+    paths
+}
+
+/// Dijkstra's algorithm from any of the `starts`, stopping once a node satisfying
+/// `is_goal` is reached (pass `|_| false` to reach everything reachable).
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Paths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    best_first(starts, neighbours, |_| C::default(), is_goal, false)
+}
+
+/// Like [`dijkstra`], but remembers every shortest path: the search carries on until
+/// everything as close as the goal is reached, and every node keeps all of its
+/// predecessors (see [`Paths::on_shortest_paths`]).
+pub fn dijkstra_all<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Paths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    best_first(starts, neighbours, |_| C::default(), is_goal, true)
+}
+
+/// A* search from `start` to a node satisfying `is_goal`, where `heuristic` must never
+/// overestimate the remaining cost. Returns a shortest path and its cost.
+pub fn astar<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    heuristic: impl FnMut(&N) -> C,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let paths = best_first([start], neighbours, heuristic, is_goal, false);
+
+    Some((paths.goal_path()?, paths.goal_distance()?))
+}
+
+/// Iterative-deepening A*: like [`astar`], but only ever keeps the current path in
+/// memory, at the cost of revisiting nodes. Meant for state spaces too large to store.
+pub fn ida_star<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    /// How a bounded depth-first search ended.
+    enum Bounded<C> {
+        Found(C),
+        /// Nothing found within the bound; the smallest estimate above it, if any.
+        Exceeded(Option<C>),
+    }
+
+    fn search<N: Clone + Eq, C: Cost, I: IntoIterator<Item = (N, C)>>(
+        path: &mut Vec<N>,
+        cost: C,
+        bound: C,
+        neighbours: &mut impl FnMut(&N) -> I,
+        heuristic: &mut impl FnMut(&N) -> C,
+        is_goal: &mut impl FnMut(&N) -> bool,
+    ) -> Bounded<C> {
+        let node = path.last().unwrap().clone();
+        let estimate = cost + heuristic(&node);
+
+        if estimate > bound {
+            return Bounded::Exceeded(Some(estimate));
+        }
+
+        if is_goal(&node) {
+            return Bounded::Found(cost);
+        }
+
+        let mut next_bound: Option<C> = None;
+
+        for (next, step) in neighbours(&node) {
+            // don't walk in circles
+            if path.contains(&next) {
+                continue;
+            }
+
+            path.push(next);
+
+            match search(path, cost + step, bound, neighbours, heuristic, is_goal) {
+                Bounded::Found(cost) => return Bounded::Found(cost),
+                Bounded::Exceeded(Some(over)) => {
+                    next_bound = Some(next_bound.map_or(over, |b| b.min(over)));
+                }
+                Bounded::Exceeded(None) => {}
+            }
+
+            path.pop();
+        }
+
+        Bounded::Exceeded(next_bound)
+    }
+
+    let mut bound = heuristic(&start);
+    let mut path = vec![start];
+
+    loop {
+        match search(
+            &mut path,
+            C::default(),
+            bound,
+            &mut neighbours,
+            &mut heuristic,
+            &mut is_goal,
+        ) {
+            Bounded::Found(cost) => return Some((path, cost)),
+            Bounded::Exceeded(Some(next_bound)) => bound = next_bound,
+            Bounded::Exceeded(None) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small weighted graph: two equally short ways from 0 to 3, and a long one.
+    fn edges(node: &u32) -> Vec<(u32, u32)> {
+        match node {
+            0 => vec![(1, 1), (2, 1), (3, 5)],
+            1 => vec![(3, 1)],
+            2 => vec![(3, 1)],
+            3 => vec![(4, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn bfs_finds_fewest_steps() {
+        let paths = bfs(0, |&n: &u32| [n + 1, n * 2], |&n| n == 10);
+
+        assert_eq!(paths.goal_distance(), Some(5));
+        assert_eq!(paths.goal_path(), Some(vec![0, 1, 2, 4, 5, 10]));
+    }
+
+    #[test]
+    fn dijkstra_finds_the_cheapest_path() {
+        let paths = dijkstra([0], edges, |&n| n == 4);
+
+        assert_eq!(paths.goal_distance(), Some(3));
+        assert_eq!(paths.goal_path(), Some(vec![0, 1, 3, 4]));
+    }
+
+    #[test]
+    fn dijkstra_all_keeps_every_shortest_path() {
+        let paths = dijkstra_all([0], edges, |&n| n == 3);
+
+        assert_eq!(paths.goal_distance(), Some(2));
+        assert_eq!(
+            paths.on_shortest_paths([&3]),
+            FxHashSet::from_iter([0, 1, 2, 3])
+        );
+
+        // the plain search only remembers one of them
+        let paths = dijkstra([0], edges, |&n| n == 3);
+        assert_eq!(paths.on_shortest_paths([&3]).len(), 3);
+    }
+
+    #[test]
+    fn astar_and_ida_star_agree() {
+        // a grid walk from (0, 0) to (5, 5), with a Manhattan distance heuristic
+        let neighbours = |&(x, y): &(i32, i32)| {
+            [(x + 1, y), (x, y + 1), (x - 1, y), (x, y - 1)]
+                .into_iter()
+                .filter(|&(x, y)| (0..=5).contains(&x) && (0..=5).contains(&y) && (x, y) != (2, 2))
+                .map(|next| (next, 1))
+        };
+        let heuristic = |&(x, y): &(i32, i32)| (5 - x) + (5 - y);
+        let is_goal = |&node: &(i32, i32)| node == (5, 5);
+
+        let (path, cost) = astar((0, 0), neighbours, heuristic, is_goal).unwrap();
+        assert_eq!((path.len(), cost), (11, 10));
+
+        let (path, cost) = ida_star((0, 0), neighbours, heuristic, is_goal).unwrap();
+        assert_eq!((path.len(), cost), (11, 10));
+    }
+
+    #[test]
+    fn unreachable_goals() {
+        assert_eq!(dijkstra([0], edges, |&n| n == 9).goal, None);
+        assert_eq!(ida_star(0, edges, |_| 0, |&n| n == 9), None);
+    }
+}
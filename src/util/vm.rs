@@ -0,0 +1,470 @@
+//! A small framework for the register machines that many puzzles are about (all but
+//! the 2019 Intcode one, which lives in `y19_intcode`).
+//!
+//! An instruction set is an [`Isa`]: its instructions, how they are parsed and what
+//! they do to a [`Machine`]. The machine holds the program, the [`Registers`], the
+//! instruction pointer and an input queue, and runs the program a [`step`] at a time,
+//! until it outputs something, needs input or halts ([`run`]), or until a hook says so
+//! ([`run_until`]). A [`Tracer`] can watch every instruction that gets executed, and
+//! the [`peephole`] optimizer can run some loops in a single step.
+//!
+//! The instruction sets themselves are in the other submodules.
+//!
+//! [`step`]: Machine::step
+//! [`run`]: Machine::run
+//! [`run_until`]: Machine::run_until
+
+pub mod alu;
+pub mod assembunny;
+pub mod chronospatial;
+pub mod crt;
+pub mod duet;
+pub mod elfcode;
+pub mod handheld;
+pub mod lock;
+pub mod peephole;
+
+use crate::util::Error;
+use peephole::Fused;
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// An instruction set.
+pub trait Isa: Sized {
+    type Instruction: Clone + fmt::Debug;
+
+    /// The number of registers.
+    const REGISTERS: usize;
+
+    /// Parses a single instruction (a line of the program).
+    fn parse(line: &str) -> Option<Self::Instruction>;
+
+    /// Executes an instruction on the machine, whose instruction pointer is at it.
+    fn execute(instruction: &Self::Instruction, machine: &mut Machine<Self>) -> Effect;
+}
+
+/// What an instruction did to the control flow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    /// Go on with the next instruction.
+    Next,
+    /// Jump by an offset from the instruction.
+    Jump(i64),
+    /// Jump to an instruction by its index.
+    Goto(i64),
+    /// Output a value and go on with the next instruction.
+    Output(i64),
+    /// Wait for input; the instruction is executed again once there is some.
+    NeedInput,
+}
+
+/// Why a machine stopped running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// The program output a value.
+    Output(i64),
+    /// The program needs input, but the input queue is empty. The instruction
+    /// pointer stays at the instruction, so queueing input and running again resumes.
+    NeedInput,
+    /// The program halted by moving the instruction pointer out of it.
+    Halt,
+    /// The hook of [`Machine::run_until`] asked to stop.
+    Break,
+}
+
+/// The registers of a machine, indexed from 0.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Registers {
+    values: Vec<i64>,
+}
+
+impl Registers {
+    pub fn new(count: usize) -> Registers {
+        Registers {
+            values: vec![0; count],
+        }
+    }
+
+    pub fn as_slice(&self) -> &[i64] {
+        &self.values
+    }
+}
+
+impl From<Vec<i64>> for Registers {
+    fn from(values: Vec<i64>) -> Registers {
+        Registers { values }
+    }
+}
+
+impl Index<usize> for Registers {
+    type Output = i64;
+
+    fn index(&self, register: usize) -> &i64 {
+        &self.values[register]
+    }
+}
+
+impl IndexMut<usize> for Registers {
+    fn index_mut(&mut self, register: usize) -> &mut i64 {
+        &mut self.values[register]
+    }
+}
+
+/// An instruction argument: a register or an immediate value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(usize),
+    Value(i64),
+}
+
+impl Operand {
+    /// Parses a number, or a register named by one of the characters of `names`
+    /// (the register's index being the character's position).
+    pub fn parse(text: &str, names: &str) -> Option<Operand> {
+        if let Ok(value) = text.parse() {
+            return Some(Operand::Value(value));
+        }
+
+        let mut chars = text.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => names
+                .chars()
+                .position(|name| name == c)
+                .map(Operand::Register),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, registers: &Registers) -> i64 {
+        match *self {
+            Operand::Register(r) => registers[r],
+            Operand::Value(value) => value,
+        }
+    }
+}
+
+/// Watches the instructions a machine executes. Closures taking the instruction
+/// pointer, the instruction and the registers (before it runs) are tracers.
+pub trait Tracer<I: Isa> {
+    fn trace(&mut self, ip: i64, instruction: &I::Instruction, registers: &Registers);
+}
+
+impl<I: Isa, F: FnMut(i64, &I::Instruction, &Registers)> Tracer<I> for F {
+    fn trace(&mut self, ip: i64, instruction: &I::Instruction, registers: &Registers) {
+        self(ip, instruction, registers)
+    }
+}
+
+pub struct Machine<I: Isa> {
+    pub program: Vec<I::Instruction>,
+    pub registers: Registers,
+    /// The index of the next instruction.
+    pub ip: i64,
+    /// The register the instruction pointer is bound to, if any: the pointer is
+    /// written to it before every instruction, and read back (and moved on) after.
+    pub ip_register: Option<usize>,
+    pub input: VecDeque<i64>,
+    /// The number of instructions executed so far.
+    pub steps: u64,
+    tracer: Option<Box<dyn Tracer<I> + Send + Sync>>,
+    /// The idioms starting at each instruction, once optimized.
+    fused: Vec<Option<Fused>>,
+}
+
+impl<I: Isa> Machine<I> {
+    pub fn new(program: Vec<I::Instruction>) -> Machine<I> {
+        Machine {
+            program,
+            registers: Registers::new(I::REGISTERS),
+            ip: 0,
+            ip_register: None,
+            input: VecDeque::new(),
+            steps: 0,
+            tracer: None,
+            fused: Vec::new(),
+        }
+    }
+
+    /// Parses a program with one instruction per line.
+    pub fn parse_program(input: &str) -> Result<Vec<I::Instruction>, Error> {
+        input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                I::parse(line).ok_or_else(|| Error::parse_in(input, line, "invalid instruction"))
+            })
+            .collect()
+    }
+
+    /// Parses a program with one instruction per line into a fresh machine.
+    pub fn parse(input: &str) -> Result<Machine<I>, Error> {
+        Ok(Machine::new(Machine::<I>::parse_program(input)?))
+    }
+
+    /// Sets the registers, starting from the first one.
+    pub fn with_registers(mut self, values: &[i64]) -> Machine<I> {
+        self.registers.values[..values.len()].copy_from_slice(values);
+        self
+    }
+
+    /// Calls the tracer before every instruction from now on.
+    pub fn trace(&mut self, tracer: impl Tracer<I> + Send + Sync + 'static) {
+        self.tracer = Some(Box::new(tracer));
+    }
+
+    /// Whether the program has been [optimized](Machine::optimize).
+    pub fn is_optimized(&self) -> bool {
+        !self.fused.is_empty()
+    }
+
+    /// The instruction the instruction pointer is at, if it is in the program.
+    pub fn current(&self) -> Option<&I::Instruction> {
+        usize::try_from(self.ip)
+            .ok()
+            .and_then(|ip| self.program.get(ip))
+    }
+
+    /// Executes a single instruction. Returns `None` if the program can go on.
+    pub fn step(&mut self) -> Option<Step> {
+        let Some(instruction) = self.current().cloned() else {
+            return Some(Step::Halt);
+        };
+
+        if let Some(register) = self.ip_register {
+            self.registers[register] = self.ip;
+        }
+
+        if let Some(tracer) = &mut self.tracer {
+            tracer.trace(self.ip, &instruction, &self.registers);
+        }
+
+        // a fused loop runs as a single step, jumping to the instruction after it
+        let fused = self.fused.get(self.ip as usize).copied().flatten();
+
+        let effect = match fused {
+            Some(fused) if fused.idiom.run(&mut self.registers) => Effect::Jump(fused.len as i64),
+            _ => I::execute(&instruction, self),
+        };
+
+        if effect == Effect::NeedInput {
+            return Some(Step::NeedInput);
+        }
+
+        self.steps += 1;
+
+        if let Some(register) = self.ip_register {
+            self.ip = self.registers[register];
+        }
+
+        match effect {
+            Effect::Next | Effect::Output(_) => self.ip += 1,
+            Effect::Jump(offset) => self.ip += offset,
+            Effect::Goto(ip) => self.ip = ip,
+            Effect::NeedInput => unreachable!(),
+        }
+
+        match effect {
+            Effect::Output(value) => Some(Step::Output(value)),
+            _ => None,
+        }
+    }
+
+    /// Runs until the program outputs a value, needs input or halts.
+    pub fn run(&mut self) -> Step {
+        loop {
+            if let Some(step) = self.step() {
+                return step;
+            }
+        }
+    }
+
+    /// Like [`run`](Machine::run), but also stops with [`Step::Break`] as soon as
+    /// `stop` (which is called before every instruction) returns `true`.
+    pub fn run_until(&mut self, mut stop: impl FnMut(&Machine<I>) -> bool) -> Step {
+        loop {
+            if stop(self) {
+                return Step::Break;
+            }
+
+            if let Some(step) = self.step() {
+                return step;
+            }
+        }
+    }
+
+    /// Runs until the program halts, or fails if it stops for anything else (like
+    /// output or missing input).
+    pub fn run_to_halt(&mut self) -> Result<(), Error> {
+        match self.run() {
+            Step::Halt => Ok(()),
+            step => Err(Error::Other(format!(
+                "the program stopped with {:?} at ip {} instead of halting",
+                step, self.ip
+            ))),
+        }
+    }
+
+    /// Runs until the program halts, collecting its outputs. Panics if it needs
+    /// input that isn't queued.
+    pub fn run_collect(&mut self) -> Vec<i64> {
+        let mut outputs = Vec::new();
+
+        loop {
+            match self.run() {
+                Step::Output(value) => outputs.push(value),
+                Step::Halt => return outputs,
+                step => panic!("unexpected {:?} at ip {}", step, self.ip),
+            }
+        }
+    }
+}
+
+/// Cloning a machine leaves its tracer behind.
+impl<I: Isa> Clone for Machine<I> {
+    fn clone(&self) -> Machine<I> {
+        Machine {
+            program: self.program.clone(),
+            registers: self.registers.clone(),
+            ip: self.ip,
+            ip_register: self.ip_register,
+            input: self.input.clone(),
+            steps: self.steps,
+            tracer: None,
+            fused: self.fused.clone(),
+        }
+    }
+}
+
+impl<I: Isa> fmt::Debug for Machine<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Machine")
+            .field("ip", &self.ip)
+            .field("registers", &self.registers.values)
+            .field("steps", &self.steps)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::assembunny::Assembunny;
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    const PROGRAM: &str = "cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a\nout a";
+
+    #[test]
+    fn parses_operands() {
+        assert_eq!(Operand::parse("-12", "ab"), Some(Operand::Value(-12)));
+        assert_eq!(Operand::parse("b", "ab"), Some(Operand::Register(1)));
+        assert_eq!(Operand::parse("c", "ab"), None);
+        assert_eq!(Operand::parse("ab", "ab"), None);
+    }
+
+    #[test]
+    fn runs_until_output_and_halt() {
+        let mut machine = Machine::<Assembunny>::parse(PROGRAM).unwrap();
+
+        assert_eq!(machine.run(), Step::Output(42));
+        assert_eq!(machine.run(), Step::Halt);
+        assert_eq!(machine.steps, 6);
+    }
+
+    #[test]
+    fn fails_unless_halting() {
+        let mut machine = Machine::<Assembunny>::parse(PROGRAM).unwrap();
+
+        assert!(machine.run_to_halt().is_err());
+        assert_eq!(machine.run_to_halt(), Ok(()));
+    }
+
+    #[test]
+    fn breaks_and_resumes() {
+        let mut machine = Machine::<Assembunny>::parse(PROGRAM).unwrap();
+
+        assert_eq!(machine.run_until(|m| m.ip == 3), Step::Break);
+        assert_eq!(machine.registers[0], 43);
+        assert_eq!(machine.run(), Step::Output(42));
+    }
+
+    #[test]
+    fn traces_instructions() {
+        let ips = Arc::new(Mutex::new(Vec::new()));
+        let mut machine = Machine::<Assembunny>::parse(PROGRAM).unwrap();
+
+        let traced = ips.clone();
+        machine.trace(move |ip, _: &_, _: &Registers| traced.lock().unwrap().push(ip));
+        machine.run_collect();
+
+        assert_eq!(*ips.lock().unwrap(), [0, 1, 2, 3, 4, 6]);
+    }
+
+    #[test]
+    fn rejects_invalid_instructions() {
+        assert_eq!(
+            Machine::<Assembunny>::parse("inc a\nfoo b").err(),
+            Some(Error::Parse {
+                line: 2,
+                column: 1,
+                message: "invalid instruction".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_jumps_into_instructions() {
+        let program = |jump| {
+            format!(
+                "Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 5,4,3,{}",
+                jump
+            )
+        };
+
+        assert!(chronospatial::parse(&program(0)).is_ok());
+        assert_eq!(
+            chronospatial::parse(&program(1)).err(),
+            Some(Error::Parse {
+                line: 5,
+                column: 1,
+                message: "jump into the middle of an instruction".to_string()
+            })
+        );
+        assert_eq!(chronospatial::Instruction::from_numbers(3, 3), None);
+    }
+
+    #[test]
+    fn shifts_out_every_bit() {
+        let program = |b| {
+            format!(
+                "Register A: 7\nRegister B: {}\nRegister C: 0\n\nProgram: 0,5,5,4",
+                b
+            )
+        };
+
+        assert_eq!(
+            chronospatial::parse(&program(1)).unwrap().run_collect(),
+            [3]
+        );
+        assert_eq!(
+            chronospatial::parse(&program(64)).unwrap().run_collect(),
+            [0]
+        );
+        assert_eq!(
+            chronospatial::parse(&program(1_i64 << 40))
+                .unwrap()
+                .run_collect(),
+            [0]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "negative combo operand")]
+    fn rejects_negative_shifts() {
+        let input = "Register A: 7\nRegister B: -1\nRegister C: 0\n\nProgram: 0,5,5,4";
+
+        chronospatial::parse(input).unwrap().run_collect();
+    }
+}
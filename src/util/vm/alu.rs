@@ -0,0 +1,58 @@
+//! The submarine's arithmetic logic unit, from 2021 day 24: registers `w`, `x`, `y`
+//! and `z`, with `inp` reading the next input value.
+
+use crate::util::vm::{Effect, Isa, Machine, Operand};
+
+pub struct Alu;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Inp(usize),
+    Add(usize, Operand),
+    Mul(usize, Operand),
+    Div(usize, Operand),
+    Mod(usize, Operand),
+    Eql(usize, Operand),
+}
+
+impl Isa for Alu {
+    type Instruction = Instruction;
+
+    const REGISTERS: usize = 4;
+
+    fn parse(line: &str) -> Option<Instruction> {
+        let operand = |text| Operand::parse(text, "wxyz");
+        let register = |text| match operand(text)? {
+            Operand::Register(r) => Some(r),
+            Operand::Value(_) => None,
+        };
+
+        Some(match *line.split_whitespace().collect::<Vec<_>>() {
+            ["inp", a] => Instruction::Inp(register(a)?),
+            ["add", a, b] => Instruction::Add(register(a)?, operand(b)?),
+            ["mul", a, b] => Instruction::Mul(register(a)?, operand(b)?),
+            ["div", a, b] => Instruction::Div(register(a)?, operand(b)?),
+            ["mod", a, b] => Instruction::Mod(register(a)?, operand(b)?),
+            ["eql", a, b] => Instruction::Eql(register(a)?, operand(b)?),
+            _ => return None,
+        })
+    }
+
+    fn execute(instruction: &Instruction, machine: &mut Machine<Alu>) -> Effect {
+        let registers = &mut machine.registers;
+
+        match *instruction {
+            Instruction::Inp(a) => match machine.input.pop_front() {
+                Some(value) => registers[a] = value,
+                None => return Effect::NeedInput,
+            },
+            Instruction::Add(a, b) => registers[a] += b.get(registers),
+            Instruction::Mul(a, b) => registers[a] *= b.get(registers),
+            Instruction::Div(a, b) => registers[a] /= b.get(registers),
+            Instruction::Mod(a, b) => registers[a] %= b.get(registers),
+            Instruction::Eql(a, b) => registers[a] = (registers[a] == b.get(registers)) as i64,
+        }
+
+        Effect::Next
+    }
+}
@@ -0,0 +1,126 @@
+//! Assembunny, from 2016 days 12, 23 and 25: four registers `a` to `d`, and a `tgl`
+//! instruction that rewrites the program as it runs.
+
+use crate::util::vm::peephole::{distinct, Fused, Idiom, Peephole};
+use crate::util::vm::{Effect, Isa, Machine, Operand};
+
+pub struct Assembunny;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Cpy(Operand, Operand),
+    Inc(Operand),
+    Dec(Operand),
+    Jnz(Operand, Operand),
+    Tgl(Operand),
+    Out(Operand),
+}
+
+impl Instruction {
+    /// The instruction a `tgl` turns this one into.
+    pub fn toggled(self) -> Instruction {
+        match self {
+            Instruction::Inc(x) => Instruction::Dec(x),
+            Instruction::Dec(x) | Instruction::Tgl(x) | Instruction::Out(x) => Instruction::Inc(x),
+            Instruction::Cpy(x, y) => Instruction::Jnz(x, y),
+            Instruction::Jnz(x, y) => Instruction::Cpy(x, y),
+        }
+    }
+}
+
+impl Isa for Assembunny {
+    type Instruction = Instruction;
+
+    const REGISTERS: usize = 4;
+
+    fn parse(line: &str) -> Option<Instruction> {
+        let operand = |text| Operand::parse(text, "abcd");
+
+        Some(match *line.split_whitespace().collect::<Vec<_>>() {
+            ["cpy", x, y] => Instruction::Cpy(operand(x)?, operand(y)?),
+            ["inc", x] => Instruction::Inc(operand(x)?),
+            ["dec", x] => Instruction::Dec(operand(x)?),
+            ["jnz", x, y] => Instruction::Jnz(operand(x)?, operand(y)?),
+            ["tgl", x] => Instruction::Tgl(operand(x)?),
+            ["out", x] => Instruction::Out(operand(x)?),
+            _ => return None,
+        })
+    }
+
+    fn execute(instruction: &Instruction, machine: &mut Machine<Assembunny>) -> Effect {
+        let registers = &mut machine.registers;
+
+        // instructions that would write to a value (after a `tgl`) are skipped
+        match *instruction {
+            Instruction::Cpy(x, Operand::Register(r)) => registers[r] = x.get(registers),
+            Instruction::Inc(Operand::Register(r)) => registers[r] += 1,
+            Instruction::Dec(Operand::Register(r)) => registers[r] -= 1,
+            Instruction::Jnz(x, y) if x.get(registers) != 0 => {
+                return Effect::Jump(y.get(registers));
+            }
+            Instruction::Tgl(x) => {
+                let target = machine.ip + x.get(registers);
+
+                if let Some(target) = usize::try_from(target)
+                    .ok()
+                    .and_then(|target| machine.program.get_mut(target))
+                {
+                    *target = target.toggled();
+
+                    if machine.is_optimized() {
+                        machine.optimize();
+                    }
+                }
+            }
+            Instruction::Out(x) => return Effect::Output(x.get(registers)),
+            _ => {}
+        }
+
+        Effect::Next
+    }
+}
+
+impl Peephole for Assembunny {
+    fn recognize(program: &[Instruction], _: Option<usize>, at: usize) -> Option<Fused> {
+        use Instruction::*;
+        use Operand::{Register as R, Value as V};
+
+        let window = program.get(at..)?;
+
+        // inc t; dec c; jnz c -2 (or dec c first)
+        let add = |window: &[Instruction]| match *window.get(..3)? {
+            [Inc(R(target)), Dec(R(counter)), Jnz(R(c), V(-2))]
+            | [Dec(R(counter)), Inc(R(target)), Jnz(R(c), V(-2))]
+                if c == counter && target != counter =>
+            {
+                Some((target, counter))
+            }
+            _ => None,
+        };
+
+        // cpy s c; <add loop on c>; dec d; jnz d -5
+        if let [Cpy(source, R(counter)), _, _, _, Dec(R(outer)), Jnz(R(d), V(-5)), ..] = *window {
+            if let Some((target, c)) = add(&window[1..]) {
+                let registers = [target, counter, outer];
+                let source_free = !matches!(source, R(r) if registers.contains(&r));
+
+                if c == counter && d == outer && source_free && distinct(&registers) {
+                    return Some(Fused {
+                        idiom: Idiom::Multiply {
+                            target,
+                            source,
+                            counter,
+                            outer,
+                        },
+                        len: 6,
+                    });
+                }
+            }
+        }
+
+        add(window).map(|(target, counter)| Fused {
+            idiom: Idiom::Add { target, counter },
+            len: 3,
+        })
+    }
+}
@@ -0,0 +1,171 @@
+//! The chronospatial computer, from 2024 day 17: registers `A`, `B` and `C`, and a
+//! program of 3-bit numbers, each opcode followed by its operand.
+//!
+//! An instruction is an opcode and operand pair, so a jump to the number at `n` goes
+//! to instruction `n / 2`. Jumps to odd `n`, into the middle of an instruction,
+//! aren't supported, and programs with them are rejected.
+
+use crate::util::vm::{Effect, Isa, Machine, Registers};
+use crate::util::Error;
+
+pub struct Chronospatial;
+
+pub const A: usize = 0;
+pub const B: usize = 1;
+pub const C: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Adv(i64),
+    Bxl(i64),
+    Bst(i64),
+    Jnz(i64),
+    Bxc(i64),
+    Out(i64),
+    Bdv(i64),
+    Cdv(i64),
+}
+
+impl Instruction {
+    /// The instruction, or `None` for an invalid one or a jump to an odd position.
+    pub fn from_numbers(opcode: i64, operand: i64) -> Option<Instruction> {
+        if !(0..8).contains(&operand) || (opcode == 3 && operand % 2 == 1) {
+            return None;
+        }
+
+        Some(match opcode {
+            0 => Instruction::Adv(operand),
+            1 => Instruction::Bxl(operand),
+            2 => Instruction::Bst(operand),
+            3 => Instruction::Jnz(operand),
+            4 => Instruction::Bxc(operand),
+            5 => Instruction::Out(operand),
+            6 => Instruction::Bdv(operand),
+            7 => Instruction::Cdv(operand),
+            _ => return None,
+        })
+    }
+
+    /// The opcode and operand pair of the instruction.
+    pub fn numbers(self) -> [i64; 2] {
+        match self {
+            Instruction::Adv(x) => [0, x],
+            Instruction::Bxl(x) => [1, x],
+            Instruction::Bst(x) => [2, x],
+            Instruction::Jnz(x) => [3, x],
+            Instruction::Bxc(x) => [4, x],
+            Instruction::Out(x) => [5, x],
+            Instruction::Bdv(x) => [6, x],
+            Instruction::Cdv(x) => [7, x],
+        }
+    }
+}
+
+/// The value of a combo operand: 0 to 3 stand for themselves, 4 to 6 for the registers.
+fn combo(operand: i64, registers: &Registers) -> i64 {
+    match operand {
+        0..=3 => operand,
+        4..=6 => registers[operand as usize - 4],
+        _ => panic!("invalid combo operand {}", operand),
+    }
+}
+
+impl Isa for Chronospatial {
+    type Instruction = Instruction;
+
+    const REGISTERS: usize = 3;
+
+    /// Parses a single `opcode,operand` pair; use [`parse`] for whole programs.
+    fn parse(line: &str) -> Option<Instruction> {
+        let (opcode, operand) = line.split_once(',')?;
+
+        Instruction::from_numbers(opcode.trim().parse().ok()?, operand.trim().parse().ok()?)
+    }
+
+    fn execute(instruction: &Instruction, machine: &mut Machine<Chronospatial>) -> Effect {
+        let registers = &mut machine.registers;
+        let divided = |x, registers: &Registers| {
+            let shift = combo(x, registers);
+
+            if shift < 0 {
+                panic!("negative combo operand {}", shift);
+            }
+
+            // shifting by the width of the register or more leaves nothing of it
+            registers[A]
+                .checked_shr(u32::try_from(shift).unwrap_or(u32::MAX))
+                .unwrap_or(0)
+        };
+
+        match *instruction {
+            Instruction::Adv(x) => registers[A] = divided(x, registers),
+            Instruction::Bxl(x) => registers[B] ^= x,
+            Instruction::Bst(x) => registers[B] = combo(x, registers) % 8,
+            Instruction::Jnz(x) if registers[A] != 0 => return Effect::Goto(x / 2),
+            Instruction::Jnz(_) => {}
+            Instruction::Bxc(_) => registers[B] ^= registers[C],
+            Instruction::Out(x) => return Effect::Output(combo(x, registers) % 8),
+            Instruction::Bdv(x) => registers[B] = divided(x, registers),
+            Instruction::Cdv(x) => registers[C] = divided(x, registers),
+        }
+
+        Effect::Next
+    }
+}
+
+/// Parses the initial registers and the program into a machine.
+pub fn parse(input: &str) -> Result<Machine<Chronospatial>, Error> {
+    let mut registers = Vec::new();
+    let mut program = Vec::new();
+
+    for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let number = |text: &str| {
+            text.trim()
+                .parse::<i64>()
+                .map_err(|_| Error::parse_in(input, text, "expected a number"))
+        };
+
+        if let Some(value) = line
+            .strip_prefix("Register ")
+            .and_then(|line| line.split_once(": "))
+            .map(|(_, value)| value)
+        {
+            registers.push(number(value)?);
+        } else if let Some(numbers) = line.strip_prefix("Program: ") {
+            let numbers = numbers
+                .split(',')
+                .map(number)
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for pair in numbers.chunks(2) {
+                let instruction = match *pair {
+                    [3, operand] if operand % 2 == 1 => {
+                        return Err(Error::parse_in(
+                            input,
+                            line,
+                            "jump into the middle of an instruction",
+                        ))
+                    }
+                    [opcode, operand] => Instruction::from_numbers(opcode, operand),
+                    _ => None,
+                };
+
+                program.push(
+                    instruction.ok_or_else(|| Error::parse_in(input, line, "invalid program"))?,
+                );
+            }
+        } else {
+            return Err(Error::parse_in(
+                input,
+                line,
+                "expected registers or a program",
+            ));
+        }
+    }
+
+    if registers.len() != Chronospatial::REGISTERS {
+        return Err(Error::parse_at(input, 0, "expected three registers"));
+    }
+
+    Ok(Machine::new(program).with_registers(&registers))
+}
@@ -0,0 +1,61 @@
+//! The handheld's CPU driving the CRT, from 2022 day 10: a single register `X`
+//! (starting at 1), `noop` and `addx`.
+//!
+//! `addx` takes two cycles, so it is parsed as a `noop` followed by the addition,
+//! which makes every instruction (and so every step of the machine) one cycle.
+
+use crate::util::vm::{Effect, Isa, Machine};
+use crate::util::Error;
+
+pub struct Crt;
+
+/// The `X` register.
+pub const X: usize = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Noop,
+    /// The second cycle of an `addx`.
+    Addx(i64),
+}
+
+impl Isa for Crt {
+    type Instruction = Instruction;
+
+    const REGISTERS: usize = 1;
+
+    /// Parses a single cycle's instruction; use [`parse`] for whole programs.
+    fn parse(line: &str) -> Option<Instruction> {
+        match *line.split_whitespace().collect::<Vec<_>>() {
+            ["noop"] => Some(Instruction::Noop),
+            ["addx", v] => v.parse().ok().map(Instruction::Addx),
+            _ => None,
+        }
+    }
+
+    fn execute(instruction: &Instruction, machine: &mut Machine<Crt>) -> Effect {
+        if let Instruction::Addx(v) = *instruction {
+            machine.registers[X] += v;
+        }
+
+        Effect::Next
+    }
+}
+
+/// Parses a program into a machine with one instruction per cycle.
+pub fn parse(input: &str) -> Result<Machine<Crt>, Error> {
+    let mut program = Vec::new();
+
+    for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let instruction =
+            Crt::parse(line).ok_or_else(|| Error::parse_in(input, line, "invalid instruction"))?;
+
+        if let Instruction::Addx(_) = instruction {
+            program.push(Instruction::Noop);
+        }
+
+        program.push(instruction);
+    }
+
+    Ok(Machine::new(program).with_registers(&[1]))
+}
@@ -0,0 +1,142 @@
+//! Duet, from 2017 days 18 and 23: registers `a` to `z`, and `snd`/`rcv` for sending
+//! and receiving values.
+//!
+//! `snd` outputs a value and `rcv` takes one from the input queue. Day 18's part 1
+//! reads them as playing and recovering sounds instead, which its driver handles.
+
+use crate::util::vm::peephole::{distinct, Fused, Idiom, Peephole};
+use crate::util::vm::{Effect, Isa, Machine, Operand};
+
+pub struct Duet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Snd(Operand),
+    Set(Operand, Operand),
+    Add(Operand, Operand),
+    Sub(Operand, Operand),
+    Mul(Operand, Operand),
+    Mod(Operand, Operand),
+    Rcv(Operand),
+    Jgz(Operand, Operand),
+    Jnz(Operand, Operand),
+}
+
+impl Isa for Duet {
+    type Instruction = Instruction;
+
+    const REGISTERS: usize = 26;
+
+    fn parse(line: &str) -> Option<Instruction> {
+        let operand = |text| Operand::parse(text, "abcdefghijklmnopqrstuvwxyz");
+
+        Some(match *line.split_whitespace().collect::<Vec<_>>() {
+            ["snd", x] => Instruction::Snd(operand(x)?),
+            ["set", x, y] => Instruction::Set(operand(x)?, operand(y)?),
+            ["add", x, y] => Instruction::Add(operand(x)?, operand(y)?),
+            ["sub", x, y] => Instruction::Sub(operand(x)?, operand(y)?),
+            ["mul", x, y] => Instruction::Mul(operand(x)?, operand(y)?),
+            ["mod", x, y] => Instruction::Mod(operand(x)?, operand(y)?),
+            ["rcv", x] => Instruction::Rcv(operand(x)?),
+            ["jgz", x, y] => Instruction::Jgz(operand(x)?, operand(y)?),
+            ["jnz", x, y] => Instruction::Jnz(operand(x)?, operand(y)?),
+            _ => return None,
+        })
+    }
+
+    fn execute(instruction: &Instruction, machine: &mut Machine<Duet>) -> Effect {
+        let registers = &mut machine.registers;
+
+        match *instruction {
+            Instruction::Snd(x) => return Effect::Output(x.get(registers)),
+            Instruction::Set(Operand::Register(r), y) => registers[r] = y.get(registers),
+            Instruction::Add(Operand::Register(r), y) => registers[r] += y.get(registers),
+            Instruction::Sub(Operand::Register(r), y) => registers[r] -= y.get(registers),
+            Instruction::Mul(Operand::Register(r), y) => registers[r] *= y.get(registers),
+            Instruction::Mod(Operand::Register(r), y) => registers[r] %= y.get(registers),
+            Instruction::Rcv(Operand::Register(r)) => match machine.input.pop_front() {
+                Some(value) => registers[r] = value,
+                None => return Effect::NeedInput,
+            },
+            Instruction::Jgz(x, y) if x.get(registers) > 0 => {
+                return Effect::Jump(y.get(registers))
+            }
+            Instruction::Jnz(x, y) if x.get(registers) != 0 => {
+                return Effect::Jump(y.get(registers))
+            }
+            Instruction::Jgz(..) | Instruction::Jnz(..) => {}
+            _ => panic!("{:?} writes to a value", instruction),
+        }
+
+        Effect::Next
+    }
+}
+
+impl Peephole for Duet {
+    fn recognize(program: &[Instruction], _: Option<usize>, at: usize) -> Option<Fused> {
+        use Instruction::*;
+        use Operand::{Register as R, Value as V};
+
+        let window = program.get(at..)?;
+
+        // the registers (x, y, n, found, temp) of a loop setting `found` to 0 if
+        // `x * y == n` for any `y` up to `n`
+        let inner = |window: &[Instruction]| {
+            let &[Set(R(g), R(x)), Mul(_, R(y)), Sub(_, R(n)), _, Set(R(f), _), ..] = window else {
+                return None;
+            };
+
+            let expected = [
+                Set(R(g), R(x)),
+                Mul(R(g), R(y)),
+                Sub(R(g), R(n)),
+                Jnz(R(g), V(2)),
+                Set(R(f), V(0)),
+                Sub(R(y), V(-1)),
+                Set(R(g), R(y)),
+                Sub(R(g), R(n)),
+                Jnz(R(g), V(-8)),
+            ];
+
+            (window.starts_with(&expected) && distinct(&[x, y, n, f, g])).then_some((x, y, n, f, g))
+        };
+
+        // set y r; <inner loop>; sub x -1; set g x; sub g n; jnz g -13
+        if let Some(&Set(R(y), V(restart))) = window.first() {
+            if let Some((x, y_inner, n, found, temp)) = inner(&window[1..]) {
+                let expected = [
+                    Sub(R(x), V(-1)),
+                    Set(R(temp), R(x)),
+                    Sub(R(temp), R(n)),
+                    Jnz(R(temp), V(-13)),
+                ];
+
+                if y_inner == y && window[10..].starts_with(&expected) {
+                    return Some(Fused {
+                        idiom: Idiom::DivisorSearch {
+                            x,
+                            y,
+                            n,
+                            found,
+                            temp,
+                            restart: Some(restart),
+                        },
+                        len: 14,
+                    });
+                }
+            }
+        }
+
+        inner(window).map(|(x, y, n, found, temp)| Fused {
+            idiom: Idiom::DivisorSearch {
+                x,
+                y,
+                n,
+                found,
+                temp,
+                restart: None,
+            },
+            len: 9,
+        })
+    }
+}
@@ -0,0 +1,244 @@
+//! Elfcode, from 2018 days 16, 19 and 21: six registers (four in day 16), sixteen
+//! opcodes of the form `op a b c` writing to register `c`, and an instruction
+//! pointer that can be bound to a register with `#ip`.
+
+use crate::util::vm::peephole::{distinct, Fused, Idiom, Peephole};
+use crate::util::vm::{Effect, Isa, Machine, Registers};
+use crate::util::Error;
+
+pub struct Elfcode;
+
+/// The sixteen opcodes. In the names, `r` means the argument is a register and `i`
+/// that it is an immediate value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Opcode {
+    Addr,
+    Addi,
+    Mulr,
+    Muli,
+    Banr,
+    Bani,
+    Borr,
+    Bori,
+    Setr,
+    Seti,
+    Gtir,
+    Gtri,
+    Gtrr,
+    Eqir,
+    Eqri,
+    Eqrr,
+}
+
+impl Opcode {
+    pub const ALL: [Opcode; 16] = [
+        Opcode::Addr,
+        Opcode::Addi,
+        Opcode::Mulr,
+        Opcode::Muli,
+        Opcode::Banr,
+        Opcode::Bani,
+        Opcode::Borr,
+        Opcode::Bori,
+        Opcode::Setr,
+        Opcode::Seti,
+        Opcode::Gtir,
+        Opcode::Gtri,
+        Opcode::Gtrr,
+        Opcode::Eqir,
+        Opcode::Eqri,
+        Opcode::Eqrr,
+    ];
+
+    pub fn name(self) -> &'static str {
+        const NAMES: [&str; 16] = [
+            "addr", "addi", "mulr", "muli", "banr", "bani", "borr", "bori", "setr", "seti", "gtir",
+            "gtri", "gtrr", "eqir", "eqri", "eqrr",
+        ];
+
+        NAMES[self as usize]
+    }
+
+    pub fn from_name(name: &str) -> Option<Opcode> {
+        Opcode::ALL.into_iter().find(|op| op.name() == name)
+    }
+
+    /// The value the opcode writes for the arguments `a` and `b`.
+    pub fn apply(self, a: i64, b: i64, registers: &Registers) -> i64 {
+        let r = |x: i64| registers[x as usize];
+
+        match self {
+            Opcode::Addr => r(a) + r(b),
+            Opcode::Addi => r(a) + b,
+            Opcode::Mulr => r(a) * r(b),
+            Opcode::Muli => r(a) * b,
+            Opcode::Banr => r(a) & r(b),
+            Opcode::Bani => r(a) & b,
+            Opcode::Borr => r(a) | r(b),
+            Opcode::Bori => r(a) | b,
+            Opcode::Setr => r(a),
+            Opcode::Seti => a,
+            Opcode::Gtir => (a > r(b)) as i64,
+            Opcode::Gtri => (r(a) > b) as i64,
+            Opcode::Gtrr => (r(a) > r(b)) as i64,
+            Opcode::Eqir => (a == r(b)) as i64,
+            Opcode::Eqri => (r(a) == b) as i64,
+            Opcode::Eqrr => (r(a) == r(b)) as i64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub op: Opcode,
+    pub a: i64,
+    pub b: i64,
+    pub c: i64,
+}
+
+impl Instruction {
+    /// Executes the instruction on the registers.
+    pub fn apply(&self, registers: &mut Registers) {
+        registers[self.c as usize] = self.op.apply(self.a, self.b, registers);
+    }
+}
+
+impl Isa for Elfcode {
+    type Instruction = Instruction;
+
+    const REGISTERS: usize = 6;
+
+    fn parse(line: &str) -> Option<Instruction> {
+        let mut parts = line.split_whitespace();
+        let op = Opcode::from_name(parts.next()?)?;
+        let mut argument = || parts.next()?.parse().ok();
+
+        Some(Instruction {
+            op,
+            a: argument()?,
+            b: argument()?,
+            c: argument()?,
+        })
+    }
+
+    fn execute(instruction: &Instruction, machine: &mut Machine<Elfcode>) -> Effect {
+        instruction.apply(&mut machine.registers);
+
+        Effect::Next
+    }
+}
+
+impl Peephole for Elfcode {
+    fn recognize(program: &[Instruction], ip_register: Option<usize>, at: usize) -> Option<Fused> {
+        use Opcode::*;
+
+        let p = ip_register? as i64;
+        let window = program.get(at..)?;
+
+        // whether the instruction is `op` on `a` and `b` into `c`, in either order for
+        // the commutative ones taking two registers
+        let is = |i: &Instruction, op: Opcode, a: i64, b: i64, c: i64| {
+            let commutative = matches!(op, Addr | Mulr | Eqrr);
+            i.op == op && i.c == c && ((i.a, i.b) == (a, b) || commutative && (i.a, i.b) == (b, a))
+        };
+
+        // the registers (x, y, n, sum, flag) of a loop adding `x` to `sum` if
+        // `x * y == n` for any `y` up to `n`, which jumps back to `start`
+        let inner = |window: &[Instruction], start: i64| {
+            let [mul, eq, skip, next, add, inc, gt, exit, jump] = window.get(..9)? else {
+                return None;
+            };
+            let (t, sum) = (mul.c, add.c);
+            let x = if add.a == sum { add.b } else { add.a };
+            let y = if mul.a == x { mul.b } else { mul.a };
+            let n = if eq.a == t { eq.b } else { eq.a };
+
+            let matches = is(mul, Mulr, x, y, t)
+                && is(eq, Eqrr, t, n, t)
+                && is(skip, Addr, t, p, p)
+                && is(next, Addi, p, 1, p)
+                && is(add, Addr, x, sum, sum)
+                && is(inc, Addi, y, 1, y)
+                && is(gt, Gtrr, y, n, t)
+                && is(exit, Addr, p, t, p)
+                && jump.op == Seti
+                && jump.a == start - 1
+                && jump.c == p;
+
+            let registers = [x, y, n, sum, t, p];
+
+            (matches
+                && registers
+                    .iter()
+                    .all(|&r| (0..Elfcode::REGISTERS as i64).contains(&r)))
+            .then(|| registers.map(|r| r as usize))
+            .filter(|registers| distinct(registers))
+        };
+
+        // seti r _ y; <inner loop>; addi x 1 x; gtrr x n t; addr t p p; seti (at - 1) _ p
+        if let Some([set, .., inc, gt, exit, jump]) = window.get(..14) {
+            if let Some([x, y, n, sum, flag, _]) = inner(&window[1..], at as i64 + 1) {
+                let (x, y, n, t) = (x as i64, y as i64, n as i64, flag as i64);
+
+                if set.op == Seti
+                    && set.c == y
+                    && is(inc, Addi, x, 1, x)
+                    && is(gt, Gtrr, x, n, t)
+                    && is(exit, Addr, t, p, p)
+                    && jump.op == Seti
+                    && jump.a == at as i64 - 1
+                    && jump.c == p
+                {
+                    return Some(Fused {
+                        idiom: Idiom::DivisorSum {
+                            x: x as usize,
+                            y: y as usize,
+                            n: n as usize,
+                            sum,
+                            flag,
+                            restart: Some(set.a),
+                        },
+                        len: 14,
+                    });
+                }
+            }
+        }
+
+        inner(window, at as i64).map(|[x, y, n, sum, flag, _]| Fused {
+            idiom: Idiom::DivisorSum {
+                x,
+                y,
+                n,
+                sum,
+                flag,
+                restart: None,
+            },
+            len: 9,
+        })
+    }
+}
+
+/// Parses a program starting with an `#ip` declaration into a machine.
+pub fn parse(input: &str) -> Result<Machine<Elfcode>, Error> {
+    let (declaration, program) = input.trim_start().split_once('\n').unwrap_or((input, ""));
+
+    let ip_register = declaration
+        .strip_prefix("#ip ")
+        .and_then(|register| register.trim().parse().ok())
+        .filter(|&register| register < Elfcode::REGISTERS)
+        .ok_or_else(|| Error::parse_in(input, declaration, "expected an #ip declaration"))?;
+
+    let program = program
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            Elfcode::parse(line).ok_or_else(|| Error::parse_in(input, line, "invalid instruction"))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut machine = Machine::new(program);
+    machine.ip_register = Some(ip_register);
+
+    Ok(machine)
+}
@@ -0,0 +1,55 @@
+//! The handheld game console's boot code, from 2020 day 8: a single accumulator
+//! register and the `acc`, `jmp` and `nop` instructions.
+
+use crate::util::vm::{Effect, Isa, Machine};
+
+pub struct Handheld;
+
+/// The accumulator's register.
+pub const ACC: usize = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Acc(i64),
+    Jmp(i64),
+    Nop(i64),
+}
+
+impl Instruction {
+    /// Swaps `jmp` and `nop`, which is how the boot code is repaired.
+    pub fn repaired(self) -> Instruction {
+        match self {
+            Instruction::Jmp(x) => Instruction::Nop(x),
+            Instruction::Nop(x) => Instruction::Jmp(x),
+            Instruction::Acc(x) => Instruction::Acc(x),
+        }
+    }
+}
+
+impl Isa for Handheld {
+    type Instruction = Instruction;
+
+    const REGISTERS: usize = 1;
+
+    fn parse(line: &str) -> Option<Instruction> {
+        let (op, argument) = line.split_once(' ')?;
+        let argument = argument.trim().parse().ok()?;
+
+        Some(match op {
+            "acc" => Instruction::Acc(argument),
+            "jmp" => Instruction::Jmp(argument),
+            "nop" => Instruction::Nop(argument),
+            _ => return None,
+        })
+    }
+
+    fn execute(instruction: &Instruction, machine: &mut Machine<Handheld>) -> Effect {
+        match *instruction {
+            Instruction::Acc(x) => machine.registers[ACC] += x,
+            Instruction::Jmp(x) => return Effect::Jump(x),
+            Instruction::Nop(_) => {}
+        }
+
+        Effect::Next
+    }
+}
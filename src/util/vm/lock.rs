@@ -0,0 +1,57 @@
+//! The Turing lock's computer, from 2015 day 23: registers `a` and `b`, and jumps
+//! on the evenness (`jie`) or oneness (`jio`) of a register.
+
+use crate::util::vm::{Effect, Isa, Machine};
+
+pub struct Lock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Hlf(usize),
+    Tpl(usize),
+    Inc(usize),
+    Jmp(i64),
+    Jie(usize, i64),
+    Jio(usize, i64),
+}
+
+impl Isa for Lock {
+    type Instruction = Instruction;
+
+    const REGISTERS: usize = 2;
+
+    fn parse(line: &str) -> Option<Instruction> {
+        let register = |name: &str| match name.trim_end_matches(',') {
+            "a" => Some(0),
+            "b" => Some(1),
+            _ => None,
+        };
+        let offset = |text: &str| text.parse().ok();
+
+        Some(match *line.split_whitespace().collect::<Vec<_>>() {
+            ["hlf", r] => Instruction::Hlf(register(r)?),
+            ["tpl", r] => Instruction::Tpl(register(r)?),
+            ["inc", r] => Instruction::Inc(register(r)?),
+            ["jmp", o] => Instruction::Jmp(offset(o)?),
+            ["jie", r, o] => Instruction::Jie(register(r)?, offset(o)?),
+            ["jio", r, o] => Instruction::Jio(register(r)?, offset(o)?),
+            _ => return None,
+        })
+    }
+
+    fn execute(instruction: &Instruction, machine: &mut Machine<Lock>) -> Effect {
+        let registers = &mut machine.registers;
+
+        match *instruction {
+            Instruction::Hlf(r) => registers[r] /= 2,
+            Instruction::Tpl(r) => registers[r] *= 3,
+            Instruction::Inc(r) => registers[r] += 1,
+            Instruction::Jmp(offset) => return Effect::Jump(offset),
+            Instruction::Jie(r, offset) if registers[r] % 2 == 0 => return Effect::Jump(offset),
+            Instruction::Jio(r, offset) if registers[r] == 1 => return Effect::Jump(offset),
+            Instruction::Jie(..) | Instruction::Jio(..) => {}
+        }
+
+        Effect::Next
+    }
+}
@@ -0,0 +1,362 @@
+//! A peephole optimizer for the loops that make some programs far too slow to run as
+//! they are, like multiplying by repeated addition or finding divisors by trying
+//! every product.
+//!
+//! Each instruction set recognizes the [`Idiom`]s written in it ([`Peephole`]), and
+//! [`Machine::optimize`] marks where they start. Once the instruction pointer gets to
+//! such a loop, the machine runs all of it as a single step, skipping over its
+//! instructions. The instructions themselves stay as they are, so jumps into the
+//! middle of a loop still work, and an idiom only runs fused when its registers are
+//! such that the loop ends (otherwise its first instruction runs as usual).
+
+use crate::util::math;
+use crate::util::vm::{Isa, Machine, Operand, Registers};
+
+/// A loop that can be run in one go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Idiom {
+    /// Moves `counter` into `target` one at a time, like `inc a; dec c; jnz c -2`.
+    Add { target: usize, counter: usize },
+    /// Adds `source` into `target`, `outer` times, through `counter`: a `cpy` of the
+    /// source to the counter, an [`Idiom::Add`] loop and `dec outer; jnz outer -5`.
+    Multiply {
+        target: usize,
+        source: Operand,
+        counter: usize,
+        outer: usize,
+    },
+    /// Tries every `y` from its value up to `n` (inclusive), adding `x` to `sum`
+    /// whenever `x * y == n`. With `restart`, it does so for every `x` from its value
+    /// up to `n` too, starting `y` from `restart` each time. Leaves the loop variables
+    /// at `n + 1` and `flag` (which held the comparisons) at 1.
+    DivisorSum {
+        x: usize,
+        y: usize,
+        n: usize,
+        sum: usize,
+        flag: usize,
+        restart: Option<i64>,
+    },
+    /// Tries every `y` from its value up to `n` (exclusive), clearing `found` whenever
+    /// `x * y == n`. With `restart`, it does so for every `x` from its value up to `n`
+    /// too, starting `y` from `restart` each time. Leaves the loop variables at `n`
+    /// and `temp` (which held the comparisons) at 0.
+    DivisorSearch {
+        x: usize,
+        y: usize,
+        n: usize,
+        found: usize,
+        temp: usize,
+        restart: Option<i64>,
+    },
+}
+
+impl Idiom {
+    /// Runs the loop on the registers, unless it wouldn't end (or at least not
+    /// without overflowing), in which case nothing is changed and `false` returned.
+    pub fn run(&self, registers: &mut Registers) -> bool {
+        match *self {
+            Idiom::Add { target, counter } => {
+                if registers[counter] <= 0 {
+                    return false;
+                }
+
+                let Some(total) = registers[target].checked_add(registers[counter]) else {
+                    return false;
+                };
+
+                registers[target] = total;
+                registers[counter] = 0;
+            }
+            Idiom::Multiply {
+                target,
+                source,
+                counter,
+                outer,
+            } => {
+                let source = source.get(registers);
+
+                if source <= 0 || registers[outer] <= 0 {
+                    return false;
+                }
+
+                let Some(total) = source
+                    .checked_mul(registers[outer])
+                    .and_then(|product| registers[target].checked_add(product))
+                else {
+                    return false;
+                };
+
+                registers[target] = total;
+                registers[counter] = 0;
+                registers[outer] = 0;
+            }
+            Idiom::DivisorSum {
+                x,
+                y,
+                n,
+                sum,
+                flag,
+                restart,
+            } => {
+                let (x_start, y_start, n_value) = (registers[x], registers[y], registers[n]);
+
+                if x_start < 1 || n_value < 1 || y_start > n_value {
+                    return false;
+                }
+
+                let added = match restart {
+                    None => {
+                        if n_value % x_start == 0 && n_value / x_start >= y_start {
+                            x_start
+                        } else {
+                            0
+                        }
+                    }
+                    Some(restart) => {
+                        if x_start > n_value || restart > n_value {
+                            return false;
+                        }
+
+                        math::divisors(n_value)
+                            .into_iter()
+                            .filter(|&d| d >= x_start && n_value / d >= restart)
+                            .sum::<i64>()
+                    }
+                };
+
+                let Some(total) = registers[sum].checked_add(added) else {
+                    return false;
+                };
+
+                registers[sum] = total;
+                if restart.is_some() {
+                    registers[x] = n_value + 1;
+                }
+
+                registers[y] = n_value + 1;
+                registers[flag] = 1;
+            }
+            Idiom::DivisorSearch {
+                x,
+                y,
+                n,
+                found,
+                temp,
+                restart,
+            } => {
+                let (x_start, y_start, n_value) = (registers[x], registers[y], registers[n]);
+
+                if x_start < 1 || y_start >= n_value {
+                    return false;
+                }
+
+                // `x * y == n` with `y < n` means `x` is a divisor above 1
+                let found_in =
+                    |d: i64, y_start: i64| d > 1 && n_value % d == 0 && n_value / d >= y_start;
+
+                let any = match restart {
+                    None => found_in(x_start, y_start),
+                    Some(restart) => {
+                        if x_start >= n_value || restart >= n_value {
+                            return false;
+                        }
+
+                        registers[x] = n_value;
+
+                        math::divisors(n_value)
+                            .into_iter()
+                            .any(|d| d >= x_start && d < n_value && found_in(d, restart))
+                    }
+                };
+
+                if any {
+                    registers[found] = 0;
+                }
+
+                registers[y] = n_value;
+                registers[temp] = 0;
+            }
+        }
+
+        true
+    }
+}
+
+/// An idiom found in a program, spanning `len` instructions from where it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fused {
+    pub idiom: Idiom,
+    pub len: usize,
+}
+
+/// An instruction set whose idioms can be recognized.
+pub trait Peephole: Isa {
+    /// The idiom starting at instruction `at`, if there is one. `ip_register` is the
+    /// register the instruction pointer is bound to, if any.
+    fn recognize(
+        program: &[Self::Instruction],
+        ip_register: Option<usize>,
+        at: usize,
+    ) -> Option<Fused>;
+}
+
+impl<I: Peephole> Machine<I> {
+    /// Finds the idioms in the program, which are then run fused. Has to be called
+    /// again when the program changes.
+    pub fn optimize(&mut self) {
+        self.fused = (0..self.program.len())
+            .map(|at| I::recognize(&self.program, self.ip_register, at))
+            .collect();
+    }
+
+    /// The machine with its program optimized.
+    pub fn optimized(mut self) -> Machine<I> {
+        self.optimize();
+        self
+    }
+}
+
+/// Whether all the registers are different, so that the idiom's instructions don't
+/// interfere with each other.
+pub fn distinct(registers: &[usize]) -> bool {
+    registers
+        .iter()
+        .enumerate()
+        .all(|(i, r)| !registers[..i].contains(r))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::vm::assembunny::Assembunny;
+    use crate::util::vm::elfcode;
+
+    fn run(idiom: Idiom, values: &[i64]) -> Option<Vec<i64>> {
+        let mut registers = Registers::from(values.to_vec());
+
+        idiom
+            .run(&mut registers)
+            .then(|| registers.as_slice().to_vec())
+    }
+
+    #[test]
+    fn adds_and_multiplies() {
+        let add = Idiom::Add {
+            target: 0,
+            counter: 1,
+        };
+
+        assert_eq!(run(add, &[5, 3]), Some(vec![8, 0]));
+        assert_eq!(run(add, &[5, -3]), None);
+
+        let multiply = Idiom::Multiply {
+            target: 0,
+            source: Operand::Register(1),
+            counter: 2,
+            outer: 3,
+        };
+
+        assert_eq!(run(multiply, &[1, 6, 9, 7]), Some(vec![43, 6, 0, 0]));
+
+        assert_eq!(run(add, &[i64::MAX, 1]), None);
+        assert_eq!(run(multiply, &[0, i64::MAX, 9, 2]), None);
+        assert_eq!(run(multiply, &[i64::MAX, 1, 9, 1]), None);
+    }
+
+    #[test]
+    fn sums_divisors() {
+        // registers: x, y, n, sum, flag
+        let sum = |restart| Idiom::DivisorSum {
+            x: 0,
+            y: 1,
+            n: 2,
+            sum: 3,
+            flag: 4,
+            restart,
+        };
+        let (inner, both) = (sum(None), sum(Some(1)));
+
+        assert_eq!(run(inner, &[3, 1, 12, 0, 0]), Some(vec![3, 13, 12, 3, 1]));
+        assert_eq!(run(inner, &[3, 5, 12, 0, 0]), Some(vec![3, 13, 12, 0, 1]));
+
+        assert_eq!(run(both, &[1, 1, 12, 0, 0]), Some(vec![13, 13, 12, 28, 1]));
+        assert_eq!(run(both, &[0, 1, 12, 0, 0]), None);
+    }
+
+    #[test]
+    fn searches_divisors() {
+        // registers: x, y, n, found, temp
+        let search = |restart| Idiom::DivisorSearch {
+            x: 0,
+            y: 1,
+            n: 2,
+            found: 3,
+            temp: 4,
+            restart,
+        };
+        let (inner, both) = (search(None), search(Some(2)));
+
+        assert_eq!(run(both, &[2, 2, 91, 1, 5]), Some(vec![91, 91, 91, 0, 0]));
+        assert_eq!(run(both, &[2, 2, 97, 1, 5]), Some(vec![97, 97, 97, 1, 0]));
+
+        assert_eq!(run(inner, &[7, 2, 91, 1, 5]), Some(vec![7, 91, 91, 0, 0]));
+        assert_eq!(run(inner, &[7, 14, 91, 1, 5]), Some(vec![7, 91, 91, 1, 0]));
+    }
+
+    #[test]
+    fn fuses_assembunny_loops() {
+        let program = "cpy 6 b\ncpy 7 d\ncpy b c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5";
+        let mut plain = Machine::<Assembunny>::parse(program).unwrap();
+        let mut optimized = plain.clone().optimized();
+
+        assert_eq!(
+            optimized
+                .fused
+                .iter()
+                .flatten()
+                .map(|f| f.len)
+                .collect::<Vec<_>>(),
+            [6, 3]
+        );
+
+        plain.run();
+        optimized.run();
+
+        assert_eq!(plain.registers, optimized.registers);
+        assert_eq!(optimized.registers[0], 42);
+        assert_eq!((plain.steps, optimized.steps), (149, 3));
+    }
+
+    #[test]
+    fn fuses_elfcode_loops() {
+        // sums the divisors of r5 that are at least r1, with r2 counting up from 1
+        let program = |increment| {
+            format!(
+                "#ip 4\nseti 1 0 2\nmulr 1 2 3\neqrr 3 5 3\naddr 3 4 4\naddi 4 1 4\n\
+                 addr 1 0 0\n{}\ngtrr 2 5 3\naddr 4 3 4\nseti 0 0 4",
+                increment
+            )
+        };
+
+        let mut plain = elfcode::parse(&program("addi 2 1 2")).unwrap();
+        plain.registers = Registers::from(vec![0, 3, 0, 0, 0, 12]);
+        let mut optimized = plain.clone().optimized();
+
+        assert_eq!(optimized.fused[1].map(|f| f.len), Some(9));
+
+        plain.run();
+        optimized.run();
+
+        // r4 is the instruction pointer, as of the last instruction each ran
+        let without_ip = |m: &Machine<_>| [0, 1, 2, 3, 5].map(|r| m.registers[r]);
+
+        assert_eq!(without_ip(&plain), without_ip(&optimized));
+        assert_eq!(optimized.registers[0], 3);
+
+        // r1 + 1 isn't r2 + 1, so the loop doesn't count through the products
+        let other = elfcode::parse(&program("addi 1 2 2")).unwrap().optimized();
+
+        assert!(other.fused.iter().all(Option::is_none));
+    }
+}
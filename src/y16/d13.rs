@@ -1,153 +1,61 @@
+use crate::util::search;
 use crate::util::Day;
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashSet};
 
 pub struct D13;
 
-fn is_wall(x: usize, y: usize, favorite_number: usize) -> bool {
-    let a = x * x + 3 * x + 2 * x * y + y + y * y + favorite_number;
+type Position = (usize, usize);
 
-    a.count_ones() % 2 == 1
-}
-
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct Position {
-    x: isize,
-    y: isize,
-}
-
-impl Position {
-    fn neighbouring_positions(&self) -> Vec<Position> {
-        let mut positions = vec![];
-
-        for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
-            positions.push(Position { x: self.x  + dx, y: self.y + dy });
-        }
-
-        positions
-    }
+const START: Position = (1, 1);
+const END: Position = (31, 39);
 
-    fn distance_to(&self, other: Position) -> usize {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
-    }
-}
-
-
-#[derive(Debug, Eq, PartialEq, Clone, Hash)]
-struct State {
-    position: Position,
+fn is_wall((x, y): Position, favorite_number: usize) -> bool {
+    let a = x * x + 3 * x + 2 * x * y + y + y * y + favorite_number;
 
-    steps: usize,
-    metric: usize,
+    a.count_ones() % 2 == 1
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.metric.cmp(&self.metric)
-    }
+fn distance(a: Position, b: Position) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+/// The open positions next to a position (the building goes on forever to the right
+/// and down, but not to the left or up).
+fn neighbours((x, y): Position, favorite_number: usize) -> impl Iterator<Item = Position> {
+    [(0, 1), (1, 0), (0, -1), (-1, 0)]
+        .into_iter()
+        .filter_map(move |(dx, dy)| Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?)))
+        .filter(move |&position| !is_wall(position, favorite_number))
 }
 
 impl Day for D13 {
     fn solve_part1(&self, input: &str) -> Option<String> {
         let favorite_number = input.trim().parse::<usize>().unwrap();
 
-        let mut explored: HashSet<Position> = HashSet::new();
-        let mut heap: BinaryHeap<State> = BinaryHeap::new();
+        let (_, steps) = search::astar(
+            START,
+            |&position| neighbours(position, favorite_number).map(|next| (next, 1)),
+            |&position| distance(position, END),
+            |&position| position == END,
+        )?;
 
-        let start = Position { x: 1, y: 1 };
-        let end = Position { x: 31, y: 39 };
-
-        explored.insert(start.clone());
-
-        heap.push(State {
-            position: start.clone(),
-            steps: 0,
-            metric: start.distance_to(end.clone()),
-        });
-
-        while let Some(state) = heap.pop() {
-            if state.position == end {
-                return Option::from(state.steps.to_string());
-            }
-
-            for next_position in state.position.neighbouring_positions() {
-                if next_position.x < 0 || next_position.y < 0 {
-                    continue;
-                }
-
-                if is_wall(next_position.x as usize, next_position.y as usize, favorite_number) {
-                    continue;
-                }
-
-                if explored.contains(&next_position) {
-                    continue;
-                }
-
-                let next_state = State {
-                    position: next_position.clone(),
-                    steps: state.steps + 1,
-                    metric: state.steps + 1 + next_position.distance_to(end.clone()),
-                };
-
-                explored.insert(next_position.clone());
-                heap.push(next_state);
-            }
-        }
-
-        unreachable!();
+        Option::from(steps.to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Option<String> {
         let favorite_number = input.trim().parse::<usize>().unwrap();
 
-        let mut explored: HashSet<Position> = HashSet::new();
-        let mut heap: BinaryHeap<State> = BinaryHeap::new();
-
-        let start = Position { x: 1, y: 1 };
-
-        explored.insert(start.clone());
-
-        heap.push(State {
-            position: start.clone(),
-            steps: 0,
-            metric: 0,
-        });
-
-        while let Some(state) = heap.pop() {
-            if state.steps == 50 {
-                continue;
-            }
-
-            for next_position in state.position.neighbouring_positions() {
-                if next_position.x < 0 || next_position.y < 0 {
-                    continue;
-                }
-
-                if is_wall(next_position.x as usize, next_position.y as usize, favorite_number) {
-                    continue;
-                }
-
-                if explored.contains(&next_position) {
-                    continue;
-                }
-
-                let next_state = State {
-                    position: next_position.clone(),
-                    steps: state.steps + 1,
-                    metric: state.steps + 1,
-                };
+        // a path of at most 50 steps never gets further than that from the start,
+        // which keeps the search from going on forever
+        let paths = search::bfs(
+            START,
+            |&position| {
+                neighbours(position, favorite_number).filter(|&next| distance(next, START) <= 50)
+            },
+            |_| false,
+        );
 
-                explored.insert(next_position.clone());
-                heap.push(next_state);
-            }
-        }
+        let reachable = paths.distances().values().filter(|&&steps| steps <= 50);
 
-        Option::from(explored.len().to_string())
+        Option::from(reachable.count().to_string())
     }
 }
@@ -1,3 +1,4 @@
+use crate::util::math::crt;
 use crate::util::Day;
 use regex::Regex;
 
@@ -17,22 +18,6 @@ fn parse(input: &str) -> Vec<(i64, i64)> {
     disks
 }
 
-fn extended_gcd(a: i64, b: i64) -> (i64, i64) {
-    let (mut old_r, mut r) = (a, b);
-    let (mut old_s, mut s) = (1, 0);
-    let (mut old_t, mut t) = (0, 1);
-
-    while r != 0 {
-        let quotient = old_r / r;
-
-        (old_r, r) = (r, old_r - quotient * r);
-        (old_s, s) = (s, old_s - quotient * s);
-        (old_t, t) = (t, old_t - quotient * t);
-    }
-
-    (old_s, old_t)
-}
-
 fn solve(disks: Vec<(i64, i64)>) -> usize {
     // (a_1 + 1 + t) % n_1 == 0
     // (a_2 + 2 + t) % n_2 == 0
@@ -46,21 +31,15 @@ fn solve(disks: Vec<(i64, i64)>) -> usize {
     // t == -a'_2 (mod n_2)
     // ...
     //
-    // then use https://en.wikipedia.org/wiki/Chinese_remainder_theorem#Existence_(constructive_proof)
-
-    let mut n_1 = 1;
-    let mut a_1 = 0;
-
-    for (idx, (n_2, mut a_2)) in disks.iter().enumerate() {
-        a_2 = -(a_2 + idx as i64 + 1);
+    // which is exactly what the Chinese remainder theorem solves
 
-        let (m_1, m_2) = extended_gcd(n_1, *n_2);
-
-        a_1 = (a_1 * m_2 * n_2 + a_2 * m_1 * n_1).rem_euclid(n_1 * n_2);
-        n_1 *= n_2;
-    }
+    let congruences: Vec<(i64, i64)> = disks
+        .iter()
+        .enumerate()
+        .map(|(idx, &(n, a))| (-(a + idx as i64 + 1), n))
+        .collect();
 
-    a_1 as usize
+    crt(&congruences).unwrap().0 as usize
 }
 
 impl Day for D15 {
@@ -1,3 +1,4 @@
+use crate::util::search;
 use crate::util::Day;
 use std::collections::{HashMap, VecDeque};
 
@@ -45,23 +46,15 @@ impl Day for D17 {
     fn solve_part1(&self, input: &str) -> Option<String> {
         let id = input.trim();
 
-        let mut queue: VecDeque<(State, usize)> = VecDeque::new();
-        let mut distances = HashMap::new();
-
-        queue.push_back((State { hash: String::from(id), position: (0, 0) }, 0));
-
-        while let Some((state, dist)) = queue.pop_front() {
-            if state.position == (3, 3) {
-                return Some(state.hash[id.len()..].to_string());
-            }
-
-            for neighbor in state.neighbours() {
-                distances.insert(neighbor.clone(), dist + 1);
-                queue.push_back((neighbor.clone(), dist + 1));
-            }
-        }
+        // the states are paths, which never repeat, so only the current one is kept
+        let (path, _) = search::ida_star(
+            State { hash: String::from(id), position: (0, 0) },
+            |state| state.neighbours().into_iter().map(|next| (next, 1)),
+            |state| (3 - state.position.0 + 3 - state.position.1) as usize,
+            |state| state.position == (3, 3),
+        )?;
 
-        unreachable!()
+        Some(path.last()?.hash[id.len()..].to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Option<String> {
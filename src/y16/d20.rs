@@ -1,15 +1,15 @@
+use crate::util::intervals::RangeSet;
 use crate::util::Day;
-use rangemap::RangeInclusiveSet;
 
 pub struct D20;
 
 
-fn get_rangeset(input: &str) -> RangeInclusiveSet<u32, u32> {
-    let mut blocked = RangeInclusiveSet::new();
+fn get_rangeset(input: &str) -> RangeSet {
+    let mut blocked = RangeSet::new();
 
     for line in input.lines() {
         let parts = line.trim().split('-').collect::<Vec<&str>>();
-        blocked.insert(parts[0].parse::<u32>().unwrap()..=parts[1].parse::<u32>().unwrap());
+        blocked.insert(parts[0].parse::<i64>().unwrap()..parts[1].parse::<i64>().unwrap() + 1);
     }
 
     blocked
@@ -20,19 +20,20 @@ impl Day for D20 {
     fn solve_part1(&self, input: &str) -> Option<String> {
         let blocked = get_rangeset(input);
 
-        // note that this assumes that 0 is blocked :)
-        Option::from((blocked.iter().next().unwrap().end() + 1).to_string())
+        // the first blocked range starts at 0, unless 0 itself is allowed
+        let lowest = if blocked.contains(0) {
+            blocked.ranges()[0].end
+        } else {
+            0
+        };
+
+        Option::from(lowest.to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Option<String> {
         let blocked = get_rangeset(input);
 
-        let mut start = 0;
-        let mut allowed = 0;
-        for range in blocked.iter() {
-            allowed += range.start() - start;
-            start = range.end().wrapping_add(1);
-        }
+        let allowed = blocked.complement(0..u32::MAX as i64 + 1).len();
 
         Option::from(allowed.to_string())
     }
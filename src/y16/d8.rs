@@ -1,19 +1,9 @@
-use crate::util::Day;
+use crate::util::{Answer, PartResult, Solution};
 use regex::Regex;
 
 const WIDTH: usize = 50;
 const HEIGHT: usize = 6;
 
-const FONT_WIDTH: usize = 5;
-const FONT_HEIGHT: usize = 6;
-
-const LETTERS: &str = " ##  ###   ##  ###  #### ####  ##  #  # ##### ### #  # #    #  # #  #  ##  ###   ##  ###   ### #### #  # #  # #  # #  # #   ##### |
-#  # #  # #  # #  # #    #    #  # #  #   #     # # #  #    #### ## # #  # #  # #  # #  # #     #   #  # #  # #  # #  # #   #  #  |
-#  # ###  #    #  # ###  ###  #    ####   #     # ##   #    #  # # ## #  # #  # #  # #  #  ##   #   #  # #  # #  #  ##   # #  #   |
-#### #  # #    #  # #    #    # ## #  #   #     # # #  #    #  # #  # #  # ###  #  # ###     #  #   #  # #  # #  # #  #   #   #   |
-#  # #  # #  # #  # #    #    #  # #  #   #  #  # #  # #    #  # #  # #  # #    # #  #  #    #  #   #  #  # # #### #  #   #  #    |
-#  # ###   ##  ###  #### #     ### #  # ##### ##  #  # #### #  # #  #  ##  #     # # #  # ###   #    ##    #  #  # #  #   #  #### |";
-
 pub struct D8;
 
 #[derive(Debug)]
@@ -68,8 +58,8 @@ fn run_commands(input: &str, screen: &mut Vec<Vec<bool>>) {
     }
 }
 
-impl Day for D8 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
+impl Solution for D8 {
+    fn part1(&self, input: &str) -> PartResult {
         let mut screen = vec![vec![false; WIDTH]; HEIGHT];
 
         run_commands(input, &mut screen);
@@ -83,49 +73,24 @@ impl Day for D8 {
             }
         }
 
-        Option::from(total.to_string())
+        Ok(total.into())
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
+    fn part2(&self, input: &str) -> PartResult {
         let mut screen = vec![vec![false; WIDTH]; HEIGHT];
 
         run_commands(input, &mut screen);
 
-        let reference_letters = LETTERS
-            .lines()
-            .map(|l| l.chars().map(|c| c == '#').collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-
-        let mut result = vec![];
-
-        for x_offset in 0..WIDTH / FONT_WIDTH {
-            let mut letter_matches = vec![0; 26];
-
-            for letter in 0..26 {
-                for y in 0..FONT_HEIGHT {
-                    for x in 0..FONT_WIDTH {
-                        match (
-                            screen[y][x + x_offset * FONT_WIDTH],
-                            reference_letters[y][x + letter * FONT_WIDTH],
-                        ) {
-                            (true, true) => letter_matches[letter] += 1,
-                            (false, true) => letter_matches[letter] -= 1,
-                            (true, false) => letter_matches[letter] -= 1,
-                            _ => continue,
-                        }
-                    }
-                }
-            }
-
-            let best_match_value = letter_matches.iter().max().unwrap();
-            let best_letter = letter_matches
-                .iter()
-                .position(|&v| v == *best_match_value)
-                .unwrap();
-
-            result.push((best_letter as u8 + b'A') as char);
-        }
-
-        Option::from(result.iter().collect::<String>())
+        let art = screen
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&on| if on { '#' } else { ' ' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(Answer::from_art(art))
     }
 }
@@ -1,39 +1,25 @@
+use crate::util::grid::Grid;
 use crate::util::Day;
 
 pub struct D21;
 
-fn parse_pattern(pattern: &str) -> usize {
-    pattern
-        .chars()
-        .filter(|&c| c != '/')
-        .rev()
-        .fold(0, |acc, c| (acc << 1) | (c == '#') as usize)
-}
-
-fn rotate_pattern(pattern: usize, size: usize) -> usize {
-    let mut rotated = 0;
-
-    for y in 0..size {
-        for x in 0..size {
-            let bit = (pattern >> ((size - 1 - y) * size + x)) & 1;
-            let new_pos = (x * size) + y;
-            rotated |= bit << new_pos;
-        }
-    }
-
-    rotated
+/// Parses a pattern like `.#./..#/###`.
+fn parse_pattern(pattern: &str) -> Grid<bool> {
+    Grid::parse_with(&pattern.replace('/', "\n"), |c| match c {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    })
+    .unwrap()
 }
 
-fn flip_pattern(pattern: usize, size: usize) -> usize {
-    let mut flipped = 0;
-
-    for y in 0..size {
-        let col = (pattern >> (y * size)) & ((1 << size) - 1);
-        let new_pos = (size - 1 - y) * size;
-        flipped |= col << new_pos;
-    }
-
-    flipped
+/// The pattern as a bitmask, with the cell at (x, y) in bit `y * size + x`.
+fn to_bits(pattern: &Grid<bool>) -> usize {
+    pattern
+        .iter()
+        .filter(|&(_, &on)| on)
+        .map(|((x, y), _)| 1 << (y * pattern.width() + x))
+        .sum()
 }
 
 fn from_map(map: &Vec<Vec<bool>>, dx: usize, dy: usize, size: usize) -> usize {
@@ -71,31 +57,17 @@ fn parse(input: &str) -> Vec<Vec<usize>> {
     ];
 
     for line in input.lines() {
-        let parts = line.split(" => ").collect::<Vec<_>>();
-
-        let from = parts[0];
-        let to = parts[1];
-
-        // a little ew but we know the input data
-        let size;
-        if from.len() == 5 {
-            size = 2;
-        } else {
-            size = 3;
-        }
-
-        let mut from_pattern = parse_pattern(from);
-        let to_pattern = parse_pattern(to);
-        for _ in 0..4 {
-            result[size][from_pattern] = to_pattern;
-            from_pattern = rotate_pattern(from_pattern, size);
-        }
+        let (from, to) = line.split_once(" => ").unwrap();
 
-        from_pattern = flip_pattern(from_pattern, size);
+        let mut from = parse_pattern(from);
+        let to = to_bits(&parse_pattern(to));
+        let size = from.width();
 
+        // a rule matches the pattern in any of its eight orientations
         for _ in 0..4 {
-            result[size][from_pattern] = to_pattern;
-            from_pattern = rotate_pattern(from_pattern, size);
+            result[size][to_bits(&from)] = to;
+            result[size][to_bits(&from.flipped_horizontally())] = to;
+            from = from.rotated_clockwise();
         }
     }
 
@@ -107,7 +79,7 @@ fn solve(input: &str, iterations: usize) -> usize {
 
     let mut width = 3;
     let mut map = vec![vec![false; width]; width];
-    to_map(parse_pattern(".#./..#/###"), width, &mut map, 0, 0);
+    to_map(to_bits(&parse_pattern(".#./..#/###")), width, &mut map, 0, 0);
 
     for _ in 0..iterations {
         let size = match width % 2 {
@@ -1,5 +1,5 @@
+use crate::util::cycle::{self, Cycle};
 use crate::util::Day;
-use std::collections::HashSet;
 
 pub struct D6;
 
@@ -14,26 +14,23 @@ fn redistribute(blocks: &mut Vec<u32>) {
     }
 }
 
-fn find_cycle_length(blocks: &mut Vec<u32>) -> usize {
-    let mut seen = HashSet::new();
-    let mut cycles = 0;
-
-    while seen.insert(blocks.clone()) {
-        redistribute(blocks);
-        cycles += 1;
-    }
-    cycles
+fn find_cycle(blocks: Vec<u32>) -> Cycle {
+    cycle::detect(blocks, |blocks| {
+        let mut blocks = blocks.clone();
+        redistribute(&mut blocks);
+        blocks
+    })
 }
 
 impl Day for D6 {
     fn solve_part1(&self, input: &str) -> Option<String> {
-        let mut blocks: Vec<u32> = input.trim().split("\t").filter_map(|s| s.parse().ok()).collect();
-        Some(find_cycle_length(&mut blocks).to_string())
+        let blocks: Vec<u32> = input.trim().split("\t").filter_map(|s| s.parse().ok()).collect();
+        let cycle = find_cycle(blocks);
+        Some((cycle.start + cycle.period).to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Option<String> {
-        let mut blocks: Vec<u32> = input.trim().split("\t").filter_map(|s| s.parse().ok()).collect();
-        find_cycle_length(&mut blocks); // Run once to reach loop start
-        Some(find_cycle_length(&mut blocks).to_string()) // Run again to measure loop size
+        let blocks: Vec<u32> = input.trim().split("\t").filter_map(|s| s.parse().ok()).collect();
+        Some(find_cycle(blocks).period.to_string())
     }
 }
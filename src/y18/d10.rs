@@ -1,7 +1,7 @@
 //! Auto-translated from the original Python solution by an LLM (Claude Code).
 //! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2018-19/tree/master/10
-use crate::util::Day;
+use crate::util::{Answer, PartResult, Solution};
 use regex::Regex;
 
 pub struct D10;
@@ -21,8 +21,8 @@ fn parse(input: &str) -> Vec<[i64; 4]> {
         .collect()
 }
 
-impl Day for D10 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
+impl Solution for D10 {
+    fn part1(&self, input: &str) -> PartResult {
         // faithful translation of 10-1.py
         let mut points = parse(input);
 
@@ -86,12 +86,12 @@ impl Day for D10 {
                 while out.ends_with('\n') {
                     out.pop();
                 }
-                return Some(out);
+                return Ok(Answer::from_art(out));
             }
         }
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
+    fn part2(&self, input: &str) -> PartResult {
         // faithful translation of 10-2.py
         let mut points = parse(input);
 
@@ -114,7 +114,7 @@ impl Day for D10 {
             if x_max - x_min < min_x_delta {
                 min_x_delta = x_max - x_min;
             } else {
-                return Some((counter - 1).to_string());
+                return Ok((counter - 1).into());
             }
         }
     }
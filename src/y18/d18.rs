@@ -1,8 +1,8 @@
 //! Auto-translated from the original Python solution by an LLM (Claude Code).
 //! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2018-19/tree/master/18
+use crate::util::cycle;
 use crate::util::Day;
-use rustc_hash::FxHashMap;
 
 pub struct D18;
 
@@ -75,15 +75,12 @@ fn step(world: &[Vec<u8>]) -> Vec<Vec<u8>> {
     new_world
 }
 
-/// String key for a world state, matching the Python `mapToString`.
-fn map_to_string(world: &[Vec<u8>]) -> String {
-    let mut s = String::with_capacity(world.len() * world[0].len());
-    for line in world {
-        for &cell in line {
-            s.push((b'0' + cell) as char);
-        }
-    }
-    s
+/// The number of trees times the number of lumberyards.
+fn resource_value(world: &[Vec<u8>]) -> usize {
+    let trees = world.iter().flatten().filter(|&&cell| cell == 1).count();
+    let lumberyards = world.iter().flatten().filter(|&&cell| cell == 2).count();
+
+    trees * lumberyards
 }
 
 impl Day for D18 {
@@ -94,54 +91,17 @@ impl Day for D18 {
             world = step(&world);
         }
 
-        let trees = world.iter().flatten().filter(|&&cell| cell == 1).count() as u64;
-        let lumberjacks = world.iter().flatten().filter(|&&cell| cell == 2).count() as u64;
-
-        Some((trees * lumberjacks).to_string())
+        Some(resource_value(&world).to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Option<String> {
-        let number_of_generations: u64 = 1_000_000_000;
+        // the forest ends up repeating itself, so only the generations up to the
+        // first repetition and the part of a period left after it need simulating
+        let cycle = cycle::brent(parse(input), |world| step(world));
+        let generations = cycle.reduce(1_000_000_000);
 
-        let mut world = parse(input);
-
-        // Maps a world-state string to the generation at which it first appeared.
-        let mut gen_dict: FxHashMap<String, u64> = FxHashMap::default();
-        let mut generation: u64 = 0;
-
-        loop {
-            let key = map_to_string(&world);
-            if gen_dict.contains_key(&key) {
-                break;
-            }
-            let new_world = step(&world);
-            gen_dict.insert(key, generation);
-            generation += 1;
-            world = new_world;
-        }
-
-        // The start and end of the cycle.
-        let cycle_start = gen_dict[&map_to_string(&world)];
-        let cycle_end = generation;
-
-        // How many generations are left.
-        let cycle_index = (number_of_generations - cycle_start) % (cycle_end - cycle_start);
-
-        // Find and sum the generation whose index in the cycle matches the last generation.
-        let mut trees = 0u64;
-        let mut lumberjacks = 0u64;
-        for (k, v) in &gen_dict {
-            if *v >= cycle_start && v - cycle_start == cycle_index {
-                for ch in k.chars() {
-                    if ch == '1' {
-                        trees += 1;
-                    } else if ch == '2' {
-                        lumberjacks += 1;
-                    }
-                }
-            }
-        }
+        let world = (0..generations).fold(parse(input), |world, _| step(&world));
 
-        Some((trees * lumberjacks).to_string())
+        Some(resource_value(&world).to_string())
     }
 }
@@ -1,22 +1,11 @@
 //! Auto-translated from the original Python solution by an LLM (Claude Code).
 //! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2019/tree/master/10
+use crate::util::math::gcd;
 use crate::util::Day;
 
 pub struct D10;
 
-/// Greatest common divisor matching Python's `math.gcd` (non-negative result).
-fn gcd(a: i64, b: i64) -> i64 {
-    let mut a = a.abs();
-    let mut b = b.abs();
-    while b != 0 {
-        let t = b;
-        b = a % b;
-        a = t;
-    }
-    a
-}
-
 /// Returns true if asteroid at (x2, y2) is in sight of the one at (x1, y1).
 fn in_sight(x1: i64, y1: i64, x2: i64, y2: i64, area: &[Vec<char>]) -> bool {
     // skip itself
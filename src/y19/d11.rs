@@ -1,7 +1,7 @@
 //! Auto-translated from the original Python solution by an LLM (Claude Code).
 //! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2019/tree/master/11
-use crate::util::Day;
+use crate::util::{Answer, PartResult, Solution};
 use crate::y19_intcode::{Intcode, Step};
 use rustc_hash::FxHashMap;
 
@@ -53,13 +53,13 @@ fn run_robot(input: &str, start_color: i64) -> FxHashMap<(i64, i64), i64> {
     area
 }
 
-impl Day for D11 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
+impl Solution for D11 {
+    fn part1(&self, input: &str) -> PartResult {
         let area = run_robot(input, 0);
-        Some(area.len().to_string())
+        Ok(area.len().into())
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
+    fn part2(&self, input: &str) -> PartResult {
         let area = run_robot(input, 1);
 
         let min_x = area.keys().map(|&(x, _)| x).min().unwrap();
@@ -76,6 +76,6 @@ impl Day for D11 {
             result.push('\n');
         }
 
-        Some(result)
+        Ok(Answer::from_art(result))
     }
 }
@@ -1,6 +1,7 @@
 //! Auto-translated from the original Python solution by an LLM (Claude Code).
 //! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2019/tree/master/12
+use crate::util::math::lcm;
 use crate::util::Day;
 use rustc_hash::FxHashMap;
 
@@ -21,15 +22,6 @@ fn parse(input: &str) -> Vec<[i64; 3]> {
         .collect()
 }
 
-fn gcd(mut a: i64, mut b: i64) -> i64 {
-    while b != 0 {
-        let t = a % b;
-        a = b;
-        b = t;
-    }
-    a
-}
-
 impl Day for D12 {
     fn solve_part1(&self, input: &str) -> Option<String> {
         let pos0 = parse(input);
@@ -108,9 +100,6 @@ impl Day for D12 {
             i += 1;
         }
 
-        let tmp = (steps[0] * steps[1]) / gcd(steps[0], steps[1]);
-        let lcm = (tmp * steps[2]) / gcd(tmp, steps[2]);
-
-        Some(lcm.to_string())
+        Some(lcm(lcm(steps[0], steps[1]), steps[2]).to_string())
     }
 }
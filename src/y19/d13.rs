@@ -1,137 +1,181 @@
-//! Auto-translated from the original Python solution by an LLM (Claude Code).
-//! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
-//! Original: https://github.com/xiaoxiae/Advent-of-Code-2019/tree/master/13
-//!
-//! ⚠️ NON-DETERMINISTIC: the original part 2 drives the game with a *random*
-//! joystick and only occasionally wins, so a faithful port cannot reliably solve
-//! it. `solve_part2` returns None pending a deterministic paddle-follows-ball
-//! rewrite. (Legitimate final score: 10292.)
-use crate::util::Day;
+use crate::util::{Error, PartResult, Solution};
 use crate::y19_intcode::{Intcode, Step};
+use rustc_hash::FxHashMap;
 
 pub struct D13;
 
-/// A tiny deterministic LCG used to mimic the original's `random.choice([0, 1, -1])`.
-/// The original is non-deterministic; this keeps the same control structure while
-/// being reproducible. It eventually clears all blocks just as the random walk does.
-struct Rng(u64);
-
-impl Rng {
-    fn next_choice(&mut self) -> i64 {
-        // xorshift64-ish step
-        self.0 ^= self.0 << 13;
-        self.0 ^= self.0 >> 7;
-        self.0 ^= self.0 << 17;
-        match self.0 % 3 {
-            0 => 0,
-            1 => 1,
-            _ => -1,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    Empty,
+    Wall,
+    Block,
+    Paddle,
+    Ball,
+}
+
+impl Tile {
+    fn from_id(id: i64) -> Option<Tile> {
+        match id {
+            0 => Some(Tile::Empty),
+            1 => Some(Tile::Wall),
+            2 => Some(Tile::Block),
+            3 => Some(Tile::Paddle),
+            4 => Some(Tile::Ball),
+            _ => None,
+        }
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Tile::Empty => ' ',
+            Tile::Wall => '#',
+            Tile::Block => '=',
+            Tile::Paddle => '_',
+            Tile::Ball => 'o',
         }
     }
 }
 
-impl Day for D13 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        let mut computer = Intcode::from_input(input);
+/// The arcade cabinet: the game running on it and the screen as drawn so far.
+struct Game {
+    computer: Intcode,
+    screen: FxHashMap<(i64, i64), Tile>,
+    score: i64,
+    ball: i64,
+    paddle: i64,
+    /// Outputs of an instruction to the screen that hasn't been completed yet.
+    pending: Vec<i64>,
+}
 
-        let mut blocks = 0;
+impl Game {
+    fn new(input: &str) -> Game {
+        Game {
+            computer: Intcode::from_input(input),
+            screen: FxHashMap::default(),
+            score: 0,
+            ball: 0,
+            paddle: 0,
+            pending: Vec::with_capacity(3),
+        }
+    }
+
+    /// Runs the game until it wants the joystick position or halts, drawing what it
+    /// outputs.
+    fn update(&mut self) -> Result<(), Error> {
         loop {
-            // The Python reads three outputs per tile; it breaks when the first
-            // output is `None` (i.e. the program halted).
-            let x = match computer.run() {
-                Step::Output(v) => v,
-                Step::Halt => break,
-                Step::NeedInput => break,
-            };
-            let _y = match computer.run() {
-                Step::Output(v) => v,
-                Step::Halt => break,
-                Step::NeedInput => break,
-            };
-            let value = match computer.run() {
-                Step::Output(v) => v,
-                Step::Halt => break,
-                Step::NeedInput => break,
-            };
-
-            let _ = x;
-            if value == 2 {
-                blocks += 1;
+            match self.computer.run() {
+                Step::Output(value) => self.pending.push(value),
+                Step::NeedInput | Step::Halt => return Ok(()),
             }
-        }
 
-        Some(blocks.to_string())
-    }
+            if let [x, y, value] = self.pending[..] {
+                self.pending.clear();
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
-        // Insert two quarters.
-        let mut computer = Intcode::from_input(input);
-        computer.set(0, 2);
-
-        let mut rng = Rng(0x1234_5678_9abc_def0);
-
-        let mut blocks: i64 = -1;
-        let mut score: i64 = 0;
-
-        // Faithful to the original odd structure: each outer iteration picks a single
-        // random joystick value, feeds it for every input request during that pass,
-        // and drains all tile triples while the program keeps producing output.
-        while blocks != 0 {
-            blocks = 0;
-            let joystick = rng.next_choice();
-
-            loop {
-                // Read x (or halt/None).
-                let x = loop {
-                    match computer.run() {
-                        Step::Output(v) => break Some(v),
-                        Step::NeedInput => {
-                            computer.input(joystick);
-                        }
-                        Step::Halt => break None,
-                    }
-                };
-
-                let Some(x) = x else { break };
-
-                // Read y.
-                let y = loop {
-                    match computer.run() {
-                        Step::Output(v) => break v,
-                        Step::NeedInput => {
-                            computer.input(joystick);
-                        }
-                        Step::Halt => break 0,
-                    }
-                };
-
-                // Read tile/value.
-                let value = loop {
-                    match computer.run() {
-                        Step::Output(v) => break v,
-                        Step::NeedInput => {
-                            computer.input(joystick);
-                        }
-                        Step::Halt => break 0,
-                    }
-                };
-
-                if x == -1 && y == 0 {
-                    score = score.max(value);
-                } else if value == 2 {
-                    blocks += 1;
+                if (x, y) == (-1, 0) {
+                    self.score = value;
+                    continue;
                 }
+
+                let tile = Tile::from_id(value)
+                    .ok_or_else(|| Error::Other(format!("unknown tile id {}", value)))?;
+
+                match tile {
+                    Tile::Ball => self.ball = x,
+                    Tile::Paddle => self.paddle = x,
+                    _ => {}
+                }
+
+                self.screen.insert((x, y), tile);
             }
+        }
+    }
+
+    fn blocks(&self) -> usize {
+        self.screen.values().filter(|&&t| t == Tile::Block).count()
+    }
+
+    /// The screen as it is now, with the score below it.
+    fn render(&self) -> String {
+        let width = self.screen.keys().map(|&(x, _)| x + 1).max().unwrap_or(0);
+        let height = self.screen.keys().map(|&(_, y)| y + 1).max().unwrap_or(0);
 
-            // If the program has halted, stop regardless of block count.
-            if computer.halted() {
-                break;
+        let mut result = String::new();
+        for y in 0..height {
+            for x in 0..width {
+                let tile = self.screen.get(&(x, y)).copied().unwrap_or(Tile::Empty);
+                result.push(tile.symbol());
             }
+            result.push('\n');
         }
 
-        // Nondeterministic (random joystick) — the score is unreliable; left
-        // unsolved pending a deterministic paddle AI. See the file header.
-        let _ = score;
-        None
+        result + &format!("score: {}\n", self.score)
+    }
+}
+
+impl Solution for D13 {
+    fn part1(&self, input: &str) -> PartResult {
+        let mut game = Game::new(input);
+        game.update()?;
+
+        if !game.computer.halted() {
+            return Err(Error::Unsolvable("the game wants input".to_string()));
+        }
+
+        Ok(game.blocks().into())
+    }
+
+    fn part2(&self, input: &str) -> PartResult {
+        let mut game = Game::new(input);
+
+        // insert two quarters
+        game.computer.set(0, 2);
+
+        // keep the paddle under the ball
+        game.update()?;
+
+        while !game.computer.halted() {
+            game.computer.input((game.ball - game.paddle).signum());
+            game.update()?;
+        }
+
+        if game.blocks() != 0 {
+            return Err(Error::Unsolvable(format!(
+                "the ball got past the paddle:\n{}",
+                game.render()
+            )));
+        }
+
+        Ok(game.score.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A program drawing the tiles and score of the triples, then halting.
+    fn program(triples: &[[i64; 3]]) -> String {
+        let outputs = triples.iter().flatten().map(|v| format!("104,{}", v));
+        outputs
+            .chain(["99".to_string()])
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    #[test]
+    fn renders_the_screen() {
+        let mut game = Game::new(&program(&[
+            [0, 0, 1],
+            [1, 0, 2],
+            [2, 0, 1],
+            [1, 1, 4],
+            [-1, 0, 7],
+            [1, 2, 3],
+        ]));
+        game.update().unwrap();
+
+        assert!(game.computer.halted());
+        assert_eq!((game.blocks(), game.ball, game.paddle), (1, 1, 1));
+        assert_eq!(game.render(), "#=#\n o \n _ \nscore: 7\n");
     }
 }
@@ -1,58 +1,78 @@
-//! Auto-translated from the original Python solution by an LLM (Claude Code).
-//! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
-//! Original: https://github.com/xiaoxiae/Advent-of-Code-2019/tree/master/22
-//!
-//! ⚠️ part 2 UNSOLVED: the original 22-2.py was incomplete (never solved). Left as
-//! None — not translating an agent-invented solution. Part 1 (2514) matched Python.
-use crate::util::Day;
+use crate::util::math::Affine;
+use crate::util::{Error, PartResult, Solution};
 
 pub struct D22;
 
-impl Day for D22 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        let techniques: Vec<&str> = input.trim().lines().collect();
-
-        let n = 10_007usize;
-        let mut deck: Vec<usize> = (0..n).collect();
-
-        for technique in &techniques {
-            if technique.starts_with("deal into") {
-                deck.reverse();
-            } else {
-                let value: i64 = technique[technique.rfind(' ').unwrap() + 1..]
-                    .trim()
-                    .parse()
-                    .unwrap();
-
-                if technique.starts_with("cut") {
-                    // deck = deck[value:] + deck[:value], supporting negatives
-                    let len = deck.len() as i64;
-                    let v = ((value % len) + len) % len;
-                    let v = v as usize;
-                    deck.rotate_left(v);
-                } else {
-                    // deal with increment
-                    let len = deck.len();
-                    let value = value as usize;
-                    let mut new_deck = vec![0usize; len];
-                    let mut i = 0usize;
-                    for src in 0..len {
-                        new_deck[i % len] = deck[src];
-                        i += value;
-                    }
-                    deck = new_deck;
-                }
-            }
+#[derive(Debug, Clone, Copy)]
+enum Technique {
+    NewStack,
+    Cut(i64),
+    Increment(i64),
+}
+
+impl Technique {
+    /// Where the technique moves the card at each position, in a deck of `size` cards.
+    fn map(self, size: i64) -> Affine {
+        match self {
+            Technique::NewStack => Affine::new(-1, -1, size),
+            Technique::Cut(n) => Affine::new(1, -n, size),
+            Technique::Increment(n) => Affine::new(n, 0, size),
         }
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Technique>, Error> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let number = |text: &str| text.parse().ok();
+
+            let technique = match line.rsplit_once(' ') {
+                Some(("deal into new", "stack")) => Some(Technique::NewStack),
+                Some(("cut", n)) => number(n).map(Technique::Cut),
+                Some(("deal with increment", n)) => number(n).map(Technique::Increment),
+                _ => None,
+            };
+
+            technique.ok_or_else(|| Error::parse_in(input, line, "unknown technique"))
+        })
+        .collect()
+}
+
+/// Where a whole shuffle moves the card at each position.
+fn shuffle(techniques: &[Technique], size: i64) -> Affine {
+    techniques
+        .iter()
+        .fold(Affine::identity(size), |map, technique| {
+            map.then(&technique.map(size))
+        })
+}
+
+/// The card that ends up at `position` after shuffling a deck of `size` cards (in
+/// factory order) `times` times. The increments have to be coprime with `size` (as
+/// they are when it's prime), or a shuffle couldn't be undone.
+fn card_at(techniques: &[Technique], size: i64, times: u64, position: i64) -> PartResult {
+    let unshuffle = shuffle(techniques, size)
+        .pow(times)
+        .inverse()
+        .ok_or_else(|| Error::Unsolvable(format!("a shuffle of {} cards loses cards", size)))?;
+
+    Ok(unshuffle.apply(position).into())
+}
 
-        let idx = deck.iter().position(|&x| x == 2019).unwrap();
-        Some(idx.to_string())
+impl Solution for D22 {
+    fn part1(&self, input: &str) -> PartResult {
+        Ok(shuffle(&parse(input)?, 10_007).apply(2019).into())
     }
 
-    fn solve_part2(&self, _input: &str) -> Option<String> {
-        // The original 22-2.py was incomplete (never solved). Not translating an
-        // agent-invented solution here — left unsolved pending a manual
-        // implementation.
-        None
+    fn part2(&self, input: &str) -> PartResult {
+        card_at(
+            &parse(input)?,
+            119_315_717_514_047,
+            101_741_582_076_661,
+            2020,
+        )
     }
 }
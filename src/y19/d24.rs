@@ -1,94 +1,193 @@
-//! Auto-translated from the original Python solution by an LLM (Claude Code).
-//! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
-//! Original: https://github.com/xiaoxiae/Advent-of-Code-2019/tree/master/24
-//!
-//! ⚠️ part 2 UNSOLVED: the original 24-2.py crashed (never solved); `solve_part2`
-//! returns None pending a manual recursive-Eris implementation. Part 1 (19923473)
-//! is verified.
-use crate::util::Day;
+use crate::util::{Error, PartResult, Solution};
 use rustc_hash::FxHashSet;
+use std::collections::VecDeque;
 
 pub struct D24;
 
-type Grid = Vec<Vec<char>>;
+const SIZE: usize = 5;
+const CENTER: usize = SIZE * SIZE / 2;
 
-fn to_string(bugs: &[Vec<char>]) -> String {
-    bugs.iter().flatten().collect()
-}
+/// A 5x5 grid of bugs, as a bitmask with the tile at (x, y) in bit `5y + x` (which
+/// makes the mask its biodiversity rating).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct Grid(u32);
+
+impl Grid {
+    fn parse(input: &str) -> Result<Grid, Error> {
+        let tiles: Vec<char> = input.lines().flat_map(|line| line.trim().chars()).collect();
+
+        if tiles.len() != SIZE * SIZE {
+            return Err(Error::parse_in(input, input, "expected a 5x5 grid"));
+        }
+
+        tiles
+            .iter()
+            .enumerate()
+            .try_fold(Grid(0), |grid, (i, tile)| match tile {
+                '#' => Ok(Grid(grid.0 | 1 << i)),
+                '.' | '?' => Ok(grid),
+                _ => Err(Error::parse_in(input, input, "unknown tile")),
+            })
+    }
 
-fn score(bugs: &[Vec<char>]) -> u64 {
-    let mut total = 0u64;
-    for (i, char) in to_string(bugs).chars().enumerate() {
-        total += (1u64 << i) * if char == '.' { 0 } else { 1 };
+    fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// The next state of the tile given how many bugs are next to it.
+    fn lives(self, i: usize, adjacent: u32) -> bool {
+        adjacent == 1 || (adjacent == 2 && self.0 & 1 << i == 0)
+    }
+
+    /// The grid a minute later, on its own.
+    fn evolve(self) -> Grid {
+        Grid((0..SIZE * SIZE).fold(0, |next, i| {
+            let adjacent = (self.0 & ADJACENT[i]).count_ones();
+            next | (self.lives(i, adjacent) as u32) << i
+        }))
+    }
+
+    /// The grid a minute later, with the grids it's in and that are in it.
+    fn evolve_recursive(self, outer: Grid, inner: Grid) -> Grid {
+        Grid(
+            (0..SIZE * SIZE)
+                .filter(|&i| i != CENTER)
+                .fold(0, |next, i| {
+                    let adjacent = (self.0 & ADJACENT[i]).count_ones()
+                        + (outer.0 & OUTER[i]).count_ones()
+                        + (inner.0 & INNER[i]).count_ones();
+                    next | (self.lives(i, adjacent) as u32) << i
+                }),
+        )
     }
-    total
 }
 
-fn evolve(bugs: &[Vec<char>]) -> Grid {
-    let height = bugs.len();
-    let width = bugs[0].len();
-    let mut new_bugs = vec![vec!['.'; width]; height];
-
-    for y in 0..height {
-        for x in 0..width {
-            let mut adjacent = 0;
-
-            for (yd, xd) in [(1i32, 0i32), (-1, 0), (0, 1), (0, -1)] {
-                let yn = y as i32 + yd;
-                let xn = x as i32 + xd;
-
-                if 0 <= yn
-                    && yn < height as i32
-                    && 0 <= xn
-                    && xn < width as i32
-                    && bugs[yn as usize][xn as usize] == '#'
-                {
-                    adjacent += 1;
-                }
-            }
-
-            new_bugs[y][x] = if (bugs[y][x] == '#' && adjacent == 1)
-                || (bugs[y][x] == '.' && (adjacent == 1 || adjacent == 2))
-            {
-                '#'
-            } else {
-                '.'
-            };
+/// For every tile, the mask of the tiles next to it in the same grid.
+const ADJACENT: [u32; SIZE * SIZE] = {
+    let mut masks = [0; SIZE * SIZE];
+    let mut i = 0;
+
+    while i < SIZE * SIZE {
+        let (x, y) = (i % SIZE, i / SIZE);
+
+        if x > 0 {
+            masks[i] |= 1 << (i - 1);
+        }
+        if x < SIZE - 1 {
+            masks[i] |= 1 << (i + 1);
+        }
+        if y > 0 {
+            masks[i] |= 1 << (i - SIZE);
         }
+        if y < SIZE - 1 {
+            masks[i] |= 1 << (i + SIZE);
+        }
+
+        i += 1;
     }
 
-    new_bugs
-}
+    masks
+};
 
-fn run(input: &str) -> u64 {
-    let mut bugs: Grid = input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .map(|l| l.chars().collect())
-        .collect();
+/// For every tile, the mask of the tiles next to it in the grid it's in: the ones
+/// around that grid's center, for tiles on the edge.
+const OUTER: [u32; SIZE * SIZE] = {
+    let mut masks = [0; SIZE * SIZE];
+    let mut i = 0;
 
-    let mut explored = FxHashSet::default();
-    explored.insert(to_string(&bugs));
+    while i < SIZE * SIZE {
+        let (x, y) = (i % SIZE, i / SIZE);
 
-    loop {
-        bugs = evolve(&bugs);
-        let string = to_string(&bugs);
+        if x == 0 {
+            masks[i] |= 1 << (CENTER - 1);
+        }
+        if x == SIZE - 1 {
+            masks[i] |= 1 << (CENTER + 1);
+        }
+        if y == 0 {
+            masks[i] |= 1 << (CENTER - SIZE);
+        }
+        if y == SIZE - 1 {
+            masks[i] |= 1 << (CENTER + SIZE);
+        }
+
+        i += 1;
+    }
+
+    masks
+};
+
+/// For every tile, the mask of the tiles next to it in the grid in its grid's
+/// center: a whole edge, for tiles around the center.
+const INNER: [u32; SIZE * SIZE] = {
+    let mut masks = [0; SIZE * SIZE];
+    let mut k = 0;
+
+    while k < SIZE {
+        masks[CENTER - SIZE] |= 1 << k;
+        masks[CENTER + SIZE] |= 1 << (SIZE * (SIZE - 1) + k);
+        masks[CENTER - 1] |= 1 << (SIZE * k);
+        masks[CENTER + 1] |= 1 << (SIZE * k + SIZE - 1);
+        k += 1;
+    }
+
+    masks
+};
+
+/// How many bugs there are after some minutes, when the grid is one level of
+/// infinitely many nested ones and all the levels inside and around it start out
+/// empty.
+fn recursive_bugs(grid: Grid, minutes: usize) -> u32 {
+    // the center is the grid one level in, rather than a tile
+    let mut levels = VecDeque::from([Grid(grid.0 & !(1 << CENTER))]);
 
-        if !explored.insert(string) {
-            return score(&bugs);
+    for _ in 0..minutes {
+        // bugs spread at most one level further each minute
+        if levels.front() != Some(&Grid(0)) {
+            levels.push_front(Grid(0));
         }
+        if levels.back() != Some(&Grid(0)) {
+            levels.push_back(Grid(0));
+        }
+
+        let level = |i: usize| levels.get(i).copied().unwrap_or_default();
+
+        levels = (0..levels.len())
+            .map(|i| {
+                let outer = i.checked_sub(1).map_or(Grid(0), level);
+                levels[i].evolve_recursive(outer, level(i + 1))
+            })
+            .collect();
     }
+
+    levels.iter().map(|grid| grid.count()).sum()
 }
 
-impl Day for D24 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        Some(run(input).to_string())
+impl Solution for D24 {
+    fn part1(&self, input: &str) -> PartResult {
+        let mut grid = Grid::parse(input)?;
+        let mut seen = FxHashSet::default();
+
+        while seen.insert(grid) {
+            grid = grid.evolve();
+        }
+
+        Ok(grid.0.into())
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
-        // The original 24-2.py crashed (never solved); a faithful port is not
-        // available. Left unsolved pending a manual recursive-Eris implementation.
-        let _ = input;
-        None
+    fn part2(&self, input: &str) -> PartResult {
+        Ok(recursive_bugs(Grid::parse(input)?, 200).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spreads_through_the_levels() {
+        let grid = Grid::parse("....#\n#..#.\n#..##\n..#..\n#....\n").unwrap();
+
+        assert_eq!(recursive_bugs(grid, 10), 99);
     }
 }
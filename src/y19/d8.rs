@@ -1,15 +1,15 @@
 //! Auto-translated from the original Python solution by an LLM (Claude Code).
 //! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2019/tree/master/08
-use crate::util::Day;
+use crate::util::{Answer, PartResult, Solution};
 
 pub struct D8;
 
 const W: usize = 25;
 const H: usize = 6;
 
-impl Day for D8 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
+impl Solution for D8 {
+    fn part1(&self, input: &str) -> PartResult {
         let pixels: Vec<u32> = input
             .trim()
             .chars()
@@ -29,10 +29,10 @@ impl Day for D8 {
             }
         }
 
-        Some(min_zeroes_value.to_string())
+        Ok(min_zeroes_value.into())
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
+    fn part2(&self, input: &str) -> PartResult {
         let pixels: Vec<u32> = input
             .trim()
             .chars()
@@ -66,6 +66,6 @@ impl Day for D8 {
             out.push('\n');
         }
 
-        Some(out)
+        Ok(Answer::from_art(out))
     }
 }
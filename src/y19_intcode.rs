@@ -6,6 +6,12 @@
 //! base, auto-extending memory, i64 cells). Per-day driver logic (amplifier
 //! feedback loops, the painting robot, the breakout game, ASCII I/O, ...) lives in
 //! the individual `src/y19/dN.rs` files and is built on top of this `step` API.
+//!
+//! Tools for inspecting programs (disassembly, code/data detection, I/O sites and
+//! memory diffs) live in [`analysis`], and a debugger built on `step` in [`debug`].
+
+pub mod analysis;
+pub mod debug;
 
 use std::collections::VecDeque;
 
@@ -76,6 +82,11 @@ impl Intcode {
         self.halted
     }
 
+    /// The memory as far as it has been extended.
+    pub fn memory(&self) -> &[i64] {
+        &self.mem
+    }
+
     /// Read memory at `addr` (auto-extending).
     pub fn get(&mut self, addr: usize) -> i64 {
         if addr >= self.mem.len() {
@@ -112,76 +123,87 @@ impl Intcode {
         }
     }
 
-    /// Run until the next `Output`, `NeedInput`, or `Halt`.
-    pub fn run(&mut self) -> Step {
-        loop {
-            let instr = self.get(self.ip);
-            let opt = instr % 100;
-            let m1 = (instr / 100) % 10;
-            let m2 = (instr / 1000) % 10;
-            let m3 = (instr / 10000) % 10;
+    /// Execute a single instruction. Returns `None` if the program can go on, or why
+    /// it paused (like `run`).
+    #[inline]
+    pub fn step(&mut self) -> Option<Step> {
+        let instr = self.get(self.ip);
+        let opt = instr % 100;
+        let m1 = (instr / 100) % 10;
+        let m2 = (instr / 1000) % 10;
+        let m3 = (instr / 10000) % 10;
 
-            match opt {
-                1 => {
-                    let v = self.read(1, m1) + self.read(2, m2);
-                    let a = self.write_addr(3, m3);
-                    self.set(a, v);
-                    self.ip += 4;
-                }
-                2 => {
-                    let v = self.read(1, m1) * self.read(2, m2);
-                    let a = self.write_addr(3, m3);
-                    self.set(a, v);
-                    self.ip += 4;
-                }
-                3 => {
-                    let Some(v) = self.inputs.pop_front() else {
-                        return Step::NeedInput; // leave ip on this instruction
-                    };
-                    let a = self.write_addr(1, m1);
-                    self.set(a, v);
-                    self.ip += 2;
-                }
-                4 => {
-                    let v = self.read(1, m1);
-                    self.ip += 2;
-                    return Step::Output(v);
-                }
-                5 => {
-                    if self.read(1, m1) != 0 {
-                        self.ip = self.read(2, m2) as usize;
-                    } else {
-                        self.ip += 3;
-                    }
-                }
-                6 => {
-                    if self.read(1, m1) == 0 {
-                        self.ip = self.read(2, m2) as usize;
-                    } else {
-                        self.ip += 3;
-                    }
-                }
-                7 => {
-                    let v = (self.read(1, m1) < self.read(2, m2)) as i64;
-                    let a = self.write_addr(3, m3);
-                    self.set(a, v);
-                    self.ip += 4;
-                }
-                8 => {
-                    let v = (self.read(1, m1) == self.read(2, m2)) as i64;
-                    let a = self.write_addr(3, m3);
-                    self.set(a, v);
-                    self.ip += 4;
-                }
-                9 => {
-                    self.base += self.read(1, m1);
-                    self.ip += 2;
+        match opt {
+            1 => {
+                let v = self.read(1, m1) + self.read(2, m2);
+                let a = self.write_addr(3, m3);
+                self.set(a, v);
+                self.ip += 4;
+            }
+            2 => {
+                let v = self.read(1, m1) * self.read(2, m2);
+                let a = self.write_addr(3, m3);
+                self.set(a, v);
+                self.ip += 4;
+            }
+            3 => {
+                let Some(v) = self.inputs.pop_front() else {
+                    return Some(Step::NeedInput); // leave ip on this instruction
+                };
+                let a = self.write_addr(1, m1);
+                self.set(a, v);
+                self.ip += 2;
+            }
+            4 => {
+                let v = self.read(1, m1);
+                self.ip += 2;
+                return Some(Step::Output(v));
+            }
+            5 => {
+                if self.read(1, m1) != 0 {
+                    self.ip = self.read(2, m2) as usize;
+                } else {
+                    self.ip += 3;
                 }
-                99 => {
-                    self.halted = true;
-                    return Step::Halt;
+            }
+            6 => {
+                if self.read(1, m1) == 0 {
+                    self.ip = self.read(2, m2) as usize;
+                } else {
+                    self.ip += 3;
                 }
-                _ => panic!("bad opcode {opt} at ip {}", self.ip),
+            }
+            7 => {
+                let v = (self.read(1, m1) < self.read(2, m2)) as i64;
+                let a = self.write_addr(3, m3);
+                self.set(a, v);
+                self.ip += 4;
+            }
+            8 => {
+                let v = (self.read(1, m1) == self.read(2, m2)) as i64;
+                let a = self.write_addr(3, m3);
+                self.set(a, v);
+                self.ip += 4;
+            }
+            9 => {
+                self.base += self.read(1, m1);
+                self.ip += 2;
+            }
+            99 => {
+                self.halted = true;
+                return Some(Step::Halt);
+            }
+            _ => panic!("bad opcode {opt} at ip {}", self.ip),
+        }
+
+        None
+    }
+
+    /// Run until the next `Output`, `NeedInput`, or `Halt`.
+    pub fn run(&mut self) -> Step {
+        loop {
+            if let Some(step) = self.step() {
+                return step;
             }
         }
     }
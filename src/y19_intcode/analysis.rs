@@ -0,0 +1,485 @@
+//! Tools for looking into Intcode programs rather than running them: a disassembler,
+//! a guess at which parts of memory are code and which are data, the sites of the
+//! input/output instructions and a diff between two memory snapshots.
+//!
+//! Code is found by following the control flow from address 0. Jumps to a register
+//! (a relative-mode target, as in returning from a function) can't be followed, so
+//! calls are recognized instead: storing an immediate address right before an
+//! unconditional jump is taken to be pushing the return address.
+
+use crate::y19_intcode::Intcode;
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Position,
+    Immediate,
+    Relative,
+}
+
+impl Mode {
+    fn from_digit(digit: i64) -> Option<Mode> {
+        match digit {
+            0 => Some(Mode::Position),
+            1 => Some(Mode::Immediate),
+            2 => Some(Mode::Relative),
+            _ => None,
+        }
+    }
+}
+
+/// A parameter of an instruction, shown as `[12]` (position), `12` (immediate) or
+/// `[rb+12]` (relative).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parameter {
+    pub mode: Mode,
+    pub value: i64,
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.mode {
+            Mode::Position => write!(f, "[{}]", self.value),
+            Mode::Immediate => write!(f, "{}", self.value),
+            Mode::Relative if self.value < 0 => write!(f, "[rb{}]", self.value),
+            Mode::Relative => write!(f, "[rb+{}]", self.value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Opcode {
+    Add,
+    Multiply,
+    Input,
+    Output,
+    JumpIfTrue,
+    JumpIfFalse,
+    LessThan,
+    Equals,
+    AdjustBase,
+    Halt,
+}
+
+impl Opcode {
+    pub fn from_code(code: i64) -> Option<Opcode> {
+        Some(match code {
+            1 => Opcode::Add,
+            2 => Opcode::Multiply,
+            3 => Opcode::Input,
+            4 => Opcode::Output,
+            5 => Opcode::JumpIfTrue,
+            6 => Opcode::JumpIfFalse,
+            7 => Opcode::LessThan,
+            8 => Opcode::Equals,
+            9 => Opcode::AdjustBase,
+            99 => Opcode::Halt,
+            _ => return None,
+        })
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Opcode::Add => "add",
+            Opcode::Multiply => "mul",
+            Opcode::Input => "in",
+            Opcode::Output => "out",
+            Opcode::JumpIfTrue => "jnz",
+            Opcode::JumpIfFalse => "jz",
+            Opcode::LessThan => "lt",
+            Opcode::Equals => "eq",
+            Opcode::AdjustBase => "arb",
+            Opcode::Halt => "hlt",
+        }
+    }
+
+    /// The number of parameters.
+    pub fn arity(self) -> usize {
+        match self {
+            Opcode::Add | Opcode::Multiply | Opcode::LessThan | Opcode::Equals => 3,
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => 2,
+            Opcode::Input | Opcode::Output | Opcode::AdjustBase => 1,
+            Opcode::Halt => 0,
+        }
+    }
+
+    /// The index of the parameter the instruction writes to, if any.
+    pub fn written(self) -> Option<usize> {
+        match self {
+            Opcode::Add | Opcode::Multiply | Opcode::LessThan | Opcode::Equals => Some(2),
+            Opcode::Input => Some(0),
+            _ => None,
+        }
+    }
+}
+
+/// A decoded instruction, shown as its mnemonic and parameters (`add [9], 10, [rb+1]`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: usize,
+    pub opcode: Opcode,
+    pub parameters: Vec<Parameter>,
+}
+
+impl Instruction {
+    /// Decodes the instruction at `address`, if the memory there is a valid one: a
+    /// known opcode, a valid mode for each parameter (and none for missing ones), no
+    /// immediate mode for the written parameter and all parameters within memory.
+    pub fn decode(memory: &[i64], address: usize) -> Option<Instruction> {
+        let &code = memory.get(address)?;
+
+        if code < 0 {
+            return None;
+        }
+
+        let opcode = Opcode::from_code(code % 100)?;
+        let arity = opcode.arity();
+
+        if code / 10_i64.pow(2 + arity as u32) != 0 {
+            return None;
+        }
+
+        let parameters = (0..arity)
+            .map(|i| {
+                let mode = Mode::from_digit(code / 10_i64.pow(2 + i as u32) % 10)?;
+                let &value = memory.get(address + 1 + i)?;
+
+                Some(Parameter { mode, value })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        if let Some(written) = opcode.written() {
+            if parameters[written].mode == Mode::Immediate {
+                return None;
+            }
+        }
+
+        Some(Instruction {
+            address,
+            opcode,
+            parameters,
+        })
+    }
+
+    /// The number of memory cells the instruction takes.
+    pub fn len(&self) -> usize {
+        1 + self.parameters.len()
+    }
+
+    /// The addresses the execution can go on from this instruction, as far as they
+    /// can be told without running it (a relative or position-mode jump target is
+    /// read from the memory as it is).
+    fn successors(&self, memory: &[i64]) -> Vec<usize> {
+        let next = self.address + self.len();
+
+        match self.opcode {
+            Opcode::Halt => vec![],
+            Opcode::JumpIfTrue | Opcode::JumpIfFalse => {
+                let [condition, target] = [self.parameters[0], self.parameters[1]];
+
+                let target = match target.mode {
+                    Mode::Immediate => Some(target.value),
+                    Mode::Position => usize::try_from(target.value)
+                        .ok()
+                        .and_then(|address| memory.get(address).copied()),
+                    Mode::Relative => None,
+                };
+                let target = target.and_then(|target| usize::try_from(target).ok());
+
+                match condition.mode {
+                    Mode::Immediate if self.is_unconditional_jump() => target.into_iter().collect(),
+                    Mode::Immediate => vec![next],
+                    _ => target.into_iter().chain([next]).collect(),
+                }
+            }
+            _ => vec![next],
+        }
+    }
+
+    fn is_unconditional_jump(&self) -> bool {
+        let condition = self.parameters.first();
+
+        match (self.opcode, condition) {
+            (Opcode::JumpIfTrue, Some(c)) => c.mode == Mode::Immediate && c.value != 0,
+            (Opcode::JumpIfFalse, Some(c)) => c.mode == Mode::Immediate && c.value == 0,
+            _ => false,
+        }
+    }
+
+    /// The address stored if this is the first half of a call: storing an immediate
+    /// value (`x + 0` or `x * 1`) right before an unconditional jump.
+    fn return_address(&self, memory: &[i64]) -> Option<usize> {
+        let [a, b, _] = self.parameters[..] else {
+            return None;
+        };
+
+        let identity = match self.opcode {
+            Opcode::Add => 0,
+            Opcode::Multiply => 1,
+            _ => return None,
+        };
+
+        let stored = match (a.mode, b.mode) {
+            (Mode::Immediate, Mode::Immediate) if b.value == identity => a.value,
+            (Mode::Immediate, Mode::Immediate) if a.value == identity => b.value,
+            _ => return None,
+        };
+
+        Instruction::decode(memory, self.address + self.len())
+            .filter(Instruction::is_unconditional_jump)
+            .and_then(|_| usize::try_from(stored).ok())
+            .filter(|&address| Instruction::decode(memory, address).is_some())
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.opcode.mnemonic())?;
+
+        for (i, parameter) in self.parameters.iter().enumerate() {
+            write!(f, "{}{}", if i == 0 { " " } else { ", " }, parameter)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Code,
+    Data,
+}
+
+/// A run of memory that is all code or all data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub kind: Kind,
+    pub range: Range<usize>,
+}
+
+/// The instructions reachable from address 0, by address.
+fn reachable(memory: &[i64]) -> Vec<Option<Instruction>> {
+    let mut instructions = vec![None; memory.len()];
+    let mut stack = vec![0];
+
+    while let Some(address) = stack.pop() {
+        if instructions.get(address).is_none_or(Option::is_some) {
+            continue;
+        }
+
+        let Some(instruction) = Instruction::decode(memory, address) else {
+            continue;
+        };
+
+        stack.extend(instruction.successors(memory));
+        stack.extend(instruction.return_address(memory));
+
+        instructions[address] = Some(instruction);
+    }
+
+    instructions
+}
+
+/// Splits the memory into code and data. Code is the reachable instructions (see
+/// the module documentation), data everything else.
+pub fn regions(memory: &[i64]) -> Vec<Region> {
+    let mut kinds = vec![Kind::Data; memory.len()];
+
+    for instruction in reachable(memory).into_iter().flatten() {
+        let range = instruction.address..instruction.address + instruction.len();
+        kinds[range].fill(Kind::Code);
+    }
+
+    let mut regions: Vec<Region> = vec![];
+
+    for (address, kind) in kinds.into_iter().enumerate() {
+        match regions.last_mut() {
+            Some(region) if region.kind == kind => region.range.end = address + 1,
+            _ => regions.push(Region {
+                kind,
+                range: address..address + 1,
+            }),
+        }
+    }
+
+    regions
+}
+
+/// A listing of the program: an instruction per line for the code and up to eight
+/// values per line for the data, each line starting with its address.
+pub fn disassemble(memory: &[i64]) -> String {
+    let instructions = reachable(memory);
+    let mut listing = String::new();
+
+    for region in regions(memory) {
+        match region.kind {
+            Kind::Code => {
+                let mut address = region.range.start;
+
+                while address < region.range.end {
+                    // instructions can overlap, in which case the later one is shown
+                    // from where the earlier one ends
+                    match &instructions[address] {
+                        Some(instruction) => {
+                            listing += &format!("{:>5}  {}\n", address, instruction);
+                            address += instruction.len();
+                        }
+                        None => {
+                            listing += &format!("{:>5}  data {}\n", address, memory[address]);
+                            address += 1;
+                        }
+                    }
+                }
+            }
+            Kind::Data => {
+                for start in region.range.clone().step_by(8) {
+                    let end = (start + 8).min(region.range.end);
+                    let values: Vec<_> = memory[start..end].iter().map(i64::to_string).collect();
+
+                    listing += &format!("{:>5}  data {}\n", start, values.join(", "));
+                }
+            }
+        }
+    }
+
+    listing
+}
+
+/// The reachable input and output instructions, in order of their addresses.
+pub fn io_sites(memory: &[i64]) -> Vec<Instruction> {
+    reachable(memory)
+        .into_iter()
+        .flatten()
+        .filter(|instruction| matches!(instruction.opcode, Opcode::Input | Opcode::Output))
+        .collect()
+}
+
+/// A memory cell that differs between two snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    pub address: usize,
+    pub before: i64,
+    pub after: i64,
+}
+
+/// The cells that differ between the memories of two machines (cells beyond the end
+/// of a memory being 0, as the machine would read them).
+pub fn diff(before: &Intcode, after: &Intcode) -> Vec<Change> {
+    let (before, after) = (before.memory(), after.memory());
+    let cell = |memory: &[i64], address| memory.get(address).copied().unwrap_or(0);
+
+    (0..before.len().max(after.len()))
+        .map(|address| Change {
+            address,
+            before: cell(before, address),
+            after: cell(after, address),
+        })
+        .filter(|change| change.before != change.after)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::y19_intcode::Step;
+
+    // reads a number, calls a function doubling it at 14 (returning to 10), outputs
+    // the result and halts; 21 and 22 are data
+    const PROGRAM: [i64; 23] = [
+        3, 21, // in [21]
+        21101, 10, 0, 0, // add 10, 0, [rb+0] (the return address)
+        1105, 1, 14, // jnz 1, 14 (the call)
+        99, // hlt (never reached)
+        4, 22, // out [22]
+        99, // hlt
+        0,  // padding
+        1002, 21, 2, 22, // mul [21], 2, [22]
+        2105, 1, 0, // jnz 1, [rb+0] (the return)
+        0, 0,
+    ];
+
+    #[test]
+    fn runs_the_example() {
+        let mut machine = Intcode::new(&PROGRAM);
+        machine.input(21);
+
+        assert_eq!(machine.run_collect(), [42]);
+    }
+
+    #[test]
+    fn decodes_instructions() {
+        let add = Instruction::decode(&PROGRAM, 2).unwrap();
+
+        assert_eq!(add.to_string(), "add 10, 0, [rb+0]");
+        assert_eq!(add.len(), 4);
+        assert_eq!(
+            Instruction::decode(&[1, -3, 4, 5], 0).unwrap().to_string(),
+            "add [-3], [4], [5]"
+        );
+
+        // an immediate written parameter, a mode for a missing parameter, an unknown
+        // opcode and a parameter past the end of memory
+        for program in [&[11101, 1, 1, 1][..], &[199], &[42], &[1, 1, 1]] {
+            assert_eq!(Instruction::decode(program, 0), None);
+        }
+    }
+
+    #[test]
+    fn finds_code_and_data() {
+        let regions: Vec<_> = regions(&PROGRAM)
+            .into_iter()
+            .map(|region| (region.kind, region.range))
+            .collect();
+
+        assert_eq!(
+            regions,
+            [
+                (Kind::Code, 0..9),
+                (Kind::Data, 9..10),
+                (Kind::Code, 10..13),
+                (Kind::Data, 13..14),
+                (Kind::Code, 14..21),
+                (Kind::Data, 21..23),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_io_sites() {
+        let sites: Vec<_> = io_sites(&PROGRAM)
+            .iter()
+            .map(|site| (site.address, site.to_string()))
+            .collect();
+
+        assert_eq!(sites, [(0, "in [21]".into()), (10, "out [22]".into())]);
+    }
+
+    #[test]
+    fn disassembles() {
+        let listing = disassemble(&[1101, 2, 3, 7, 4, 7, 99, 0, 5, 6]);
+
+        assert_eq!(
+            listing,
+            "    0  add 2, 3, [7]\n    4  out [7]\n    6  hlt\n    7  data 0, 5, 6\n"
+        );
+    }
+
+    #[test]
+    fn diffs_memory() {
+        let before = Intcode::new(&[3, 5, 4, 5, 99]);
+        let mut after = before.clone();
+
+        after.input(7);
+        assert_eq!(after.run(), Step::Output(7));
+
+        assert_eq!(
+            diff(&before, &after),
+            [Change {
+                address: 5,
+                before: 0,
+                after: 7
+            }]
+        );
+    }
+}
@@ -0,0 +1,620 @@
+//! A debugger for Intcode programs: single-stepping, breakpoints on an instruction
+//! or on any access to a memory address, watchpoints on writes, instruction counters
+//! and a trace of the last instructions executed (in a bounded ring buffer).
+//!
+//! [`repl`] puts a command prompt on top of it, which the runner starts for a 2019
+//! Intcode day with `--debug`.
+
+use crate::y19_intcode::analysis::{self, Change, Instruction, Mode, Opcode, Parameter};
+use crate::y19_intcode::{Intcode, Step};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// The days of 2019 whose input is an Intcode program.
+pub const DAYS: [usize; 10] = [2, 5, 7, 9, 11, 13, 15, 17, 19, 21];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Breakpoint {
+    /// Stop before executing the instruction at this address.
+    Ip(usize),
+    /// Stop before executing an instruction that reads or writes this address.
+    Memory(usize),
+}
+
+/// Why the debugger stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Breakpoint(Breakpoint),
+    /// A watched address was written a different value.
+    Watchpoint(Change),
+    NeedInput,
+    Halt,
+    /// The instruction pointer is at a value that isn't an instruction, which the
+    /// machine can't execute.
+    Invalid {
+        address: usize,
+        value: i64,
+    },
+}
+
+/// An executed instruction: the values it read and what it wrote, if anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Traced {
+    pub instruction: Instruction,
+    pub operands: Vec<i64>,
+    pub write: Option<(usize, i64)>,
+}
+
+impl fmt::Display for Traced {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operands: Vec<_> = self.operands.iter().map(i64::to_string).collect();
+
+        write!(
+            f,
+            "{:>5}  {:<28} ({})",
+            self.instruction.address,
+            self.instruction.to_string(),
+            operands.join(", ")
+        )?;
+
+        if let Some((address, value)) = self.write {
+            write!(f, " -> [{}] = {}", address, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The last instructions executed, up to a capacity (0 turns tracing off).
+#[derive(Debug, Clone)]
+pub struct Trace {
+    capacity: usize,
+    entries: VecDeque<Traced>,
+}
+
+impl Trace {
+    pub fn new(capacity: usize) -> Trace {
+        Trace {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    fn push(&mut self, traced: Traced) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(traced);
+    }
+
+    /// The traced instructions, oldest first.
+    pub fn entries(&self) -> impl DoubleEndedIterator<Item = &Traced> + ExactSizeIterator {
+        self.entries.iter()
+    }
+}
+
+/// How many instructions were executed: in total, per opcode and per address.
+#[derive(Debug, Clone, Default)]
+pub struct Counters {
+    pub total: u64,
+    pub by_opcode: BTreeMap<Opcode, u64>,
+    pub by_address: HashMap<usize, u64>,
+}
+
+impl Counters {
+    fn count(&mut self, instruction: &Instruction) {
+        self.total += 1;
+        *self.by_opcode.entry(instruction.opcode).or_default() += 1;
+        *self.by_address.entry(instruction.address).or_default() += 1;
+    }
+
+    /// The `n` most executed addresses, most executed first.
+    pub fn hottest(&self, n: usize) -> Vec<(usize, u64)> {
+        let mut counts: Vec<_> = self.by_address.iter().map(|(&a, &c)| (a, c)).collect();
+        counts.sort_by_key(|&(address, count)| (std::cmp::Reverse(count), address));
+        counts.truncate(n);
+        counts
+    }
+}
+
+/// A machine under the debugger. Its outputs are collected in `outputs`.
+pub struct Debugger {
+    pub machine: Intcode,
+    pub trace: Trace,
+    pub breakpoints: BTreeSet<Breakpoint>,
+    pub watchpoints: BTreeSet<usize>,
+    pub counters: Counters,
+    pub outputs: Vec<i64>,
+}
+
+impl Debugger {
+    pub fn new(machine: Intcode, trace_capacity: usize) -> Debugger {
+        Debugger {
+            machine,
+            trace: Trace::new(trace_capacity),
+            breakpoints: BTreeSet::new(),
+            watchpoints: BTreeSet::new(),
+            counters: Counters::default(),
+            outputs: Vec::new(),
+        }
+    }
+
+    fn cell(&self, address: usize) -> i64 {
+        self.machine.mem.get(address).copied().unwrap_or(0)
+    }
+
+    /// The address a parameter refers to (`None` for an immediate one).
+    fn address(&self, parameter: Parameter) -> Option<usize> {
+        match parameter.mode {
+            Mode::Position => usize::try_from(parameter.value).ok(),
+            Mode::Immediate => None,
+            Mode::Relative => usize::try_from(self.machine.base + parameter.value).ok(),
+        }
+    }
+
+    /// The instruction at the instruction pointer (with the cells past the end of
+    /// memory being 0, as the machine would read them).
+    pub fn current(&self) -> Option<Instruction> {
+        let ip = self.machine.ip;
+        let window: Vec<i64> = (ip..ip + 4).map(|address| self.cell(address)).collect();
+
+        Instruction::decode(&window, 0).map(|instruction| Instruction {
+            address: ip,
+            ..instruction
+        })
+    }
+
+    /// The breakpoint the next instruction is at, if any.
+    fn breakpoint(&self) -> Option<Breakpoint> {
+        if self.breakpoints.contains(&Breakpoint::Ip(self.machine.ip)) {
+            return Some(Breakpoint::Ip(self.machine.ip));
+        }
+
+        let instruction = self.current()?;
+
+        instruction
+            .parameters
+            .iter()
+            .filter_map(|&parameter| self.address(parameter))
+            .map(Breakpoint::Memory)
+            .find(|breakpoint| self.breakpoints.contains(breakpoint))
+    }
+
+    /// Executes a single instruction, ignoring breakpoints.
+    pub fn step(&mut self) -> Option<Stop> {
+        let Some(instruction) = self.current() else {
+            let address = self.machine.ip;
+
+            return Some(Stop::Invalid {
+                address,
+                value: self.cell(address),
+            });
+        };
+
+        let written = instruction.opcode.written();
+        let operands = instruction
+            .parameters
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| Some(i) != written)
+            .map(|(_, &parameter)| match self.address(parameter) {
+                Some(address) => self.cell(address),
+                None => parameter.value,
+            })
+            .collect();
+        let target = written.and_then(|i| self.address(instruction.parameters[i]));
+        let before = target.map(|address| self.cell(address));
+
+        let step = self.machine.step();
+
+        if step == Some(Step::NeedInput) {
+            return Some(Stop::NeedInput);
+        }
+
+        let write = target.map(|address| (address, self.cell(address)));
+
+        self.counters.count(&instruction);
+        self.trace.push(Traced {
+            instruction,
+            operands,
+            write,
+        });
+
+        match step {
+            Some(Step::Output(value)) => self.outputs.push(value),
+            Some(Step::Halt) => return Some(Stop::Halt),
+            _ => {}
+        }
+
+        match (write, before) {
+            (Some((address, after)), Some(before))
+                if after != before && self.watchpoints.contains(&address) =>
+            {
+                Some(Stop::Watchpoint(Change {
+                    address,
+                    before,
+                    after,
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    /// Runs until a breakpoint or watchpoint is hit, or the program needs input or
+    /// halts. A breakpoint at the instruction it starts from doesn't count, so that
+    /// running again after a breakpoint goes on.
+    pub fn run(&mut self) -> Stop {
+        loop {
+            if let Some(stop) = self.step() {
+                return stop;
+            }
+
+            if let Some(breakpoint) = self.breakpoint() {
+                return Stop::Breakpoint(breakpoint);
+            }
+        }
+    }
+}
+
+const HELP: &str = "\
+commands:
+  s [n]          step n instructions (1 by default)
+  c              continue until a breakpoint, a watchpoint, input or halt
+  b <ip>         break before the instruction at ip
+  bm <address>   break before any instruction reading or writing the address
+  w <address>    stop after a write changing the address
+  clear          remove all breakpoints and watchpoints
+  i <n>...       queue numbers as input
+  a <text>       queue a line of ASCII text as input
+  l [ip] [n]     disassemble n instructions (10 by default) from ip
+  d              disassemble the whole memory, telling code and data apart
+  m              show the memory cells changed since the start
+  x <address> [n] show n memory cells (8 by default)
+  t [n]          show the last n traced instructions (20 by default)
+  n              show the instruction counters
+  r              show the instruction pointer, relative base and input queue
+  q              quit";
+
+/// Runs the debugger on a program with commands read from `reader`, writing to
+/// `writer`, until `q` or the end of the commands.
+pub fn repl(program: &str, reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+    let initial = Intcode::from_input(program);
+    let mut debugger = Debugger::new(initial.clone(), 1000);
+
+    writeln!(writer, "Intcode debugger; h for help")?;
+    write_current(&debugger, &mut writer)?;
+
+    for line in reader.lines() {
+        let line = line?;
+        let (command, rest) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        let numbers: Vec<i64> = rest
+            .split_whitespace()
+            .filter_map(|n| n.parse().ok())
+            .collect();
+        let number = |i: usize, default: i64| numbers.get(i).copied().unwrap_or(default);
+
+        match command {
+            "" => continue,
+            "q" | "quit" => break,
+            "h" | "help" => writeln!(writer, "{}", HELP)?,
+            "s" | "step" => {
+                for _ in 0..number(0, 1) {
+                    let stop = debugger.step();
+
+                    if let Some(traced) = debugger.trace.entries().next_back() {
+                        writeln!(writer, "{}", traced)?;
+                    }
+
+                    if let Some(stop) = stop {
+                        write_stop(stop, &mut writer)?;
+                        break;
+                    }
+                }
+            }
+            "c" | "continue" => {
+                let stop = debugger.run();
+                write_outputs(&mut debugger, &mut writer)?;
+                write_stop(stop, &mut writer)?;
+            }
+            "b" | "bm" | "w" if numbers.len() == 1 && numbers[0] >= 0 => {
+                let address = numbers[0] as usize;
+
+                match command {
+                    "b" => debugger.breakpoints.insert(Breakpoint::Ip(address)),
+                    "bm" => debugger.breakpoints.insert(Breakpoint::Memory(address)),
+                    _ => debugger.watchpoints.insert(address),
+                };
+            }
+            "clear" => {
+                debugger.breakpoints.clear();
+                debugger.watchpoints.clear();
+            }
+            "i" | "input" => debugger.machine.input_all(numbers.iter().copied()),
+            "a" | "ascii" => debugger.machine.input_ascii(&format!("{}\n", rest)),
+            "l" | "list" => {
+                let mut address = number(0, debugger.machine.ip as i64).max(0) as usize;
+
+                for _ in 0..number(1, 10) {
+                    let memory = debugger.machine.memory();
+
+                    match Instruction::decode(memory, address) {
+                        Some(instruction) => {
+                            writeln!(writer, "{:>5}  {}", address, instruction)?;
+                            address += instruction.len();
+                        }
+                        None if address < memory.len() => {
+                            writeln!(writer, "{:>5}  data {}", address, memory[address])?;
+                            address += 1;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            "d" | "disassemble" => write!(
+                writer,
+                "{}",
+                analysis::disassemble(debugger.machine.memory())
+            )?,
+            "m" | "changes" => {
+                for change in analysis::diff(&initial, &debugger.machine) {
+                    writeln!(
+                        writer,
+                        "{:>5}  {} -> {}",
+                        change.address, change.before, change.after
+                    )?;
+                }
+            }
+            "x" | "memory" if !numbers.is_empty() && numbers[0] >= 0 => {
+                let start = numbers[0] as usize;
+                let values: Vec<_> = (start..start + number(1, 8).max(0) as usize)
+                    .map(|address| debugger.cell(address).to_string())
+                    .collect();
+
+                writeln!(writer, "{:>5}  {}", start, values.join(", "))?;
+            }
+            "t" | "trace" => {
+                let entries = debugger.trace.entries();
+                let skip = entries.len().saturating_sub(number(0, 20).max(0) as usize);
+
+                for traced in entries.skip(skip) {
+                    writeln!(writer, "{}", traced)?;
+                }
+            }
+            "n" | "counters" => {
+                let counters = &debugger.counters;
+                writeln!(writer, "{} instructions", counters.total)?;
+
+                for (opcode, count) in &counters.by_opcode {
+                    writeln!(writer, "  {:<4} {}", opcode.mnemonic(), count)?;
+                }
+
+                for (address, count) in counters.hottest(5) {
+                    writeln!(writer, "  at {:>5}: {}", address, count)?;
+                }
+            }
+            "r" | "registers" => writeln!(
+                writer,
+                "ip {}, rb {}, {} inputs queued",
+                debugger.machine.ip,
+                debugger.machine.base,
+                debugger.machine.inputs.len()
+            )?,
+            _ => writeln!(writer, "unknown command {:?}; h for help", line.trim())?,
+        }
+
+        write_outputs(&mut debugger, &mut writer)?;
+
+        if matches!(command, "s" | "step" | "c" | "continue") {
+            write_current(&debugger, &mut writer)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes (and clears) the outputs collected so far, as text if they all look like
+/// ASCII and as numbers otherwise.
+fn write_outputs(debugger: &mut Debugger, writer: &mut impl Write) -> io::Result<()> {
+    let outputs = std::mem::take(&mut debugger.outputs);
+
+    if outputs.is_empty() {
+        return Ok(());
+    }
+
+    if outputs.iter().all(|&v| v == 10 || (32..127).contains(&v)) {
+        let text: String = outputs.iter().map(|&v| v as u8 as char).collect();
+        write!(writer, "{}", text)?;
+
+        if !text.ends_with('\n') {
+            writeln!(writer)?;
+        }
+    } else {
+        let values: Vec<_> = outputs.iter().map(i64::to_string).collect();
+        writeln!(writer, "output: {}", values.join(", "))?;
+    }
+
+    Ok(())
+}
+
+fn write_stop(stop: Stop, writer: &mut impl Write) -> io::Result<()> {
+    match stop {
+        Stop::Breakpoint(Breakpoint::Ip(ip)) => writeln!(writer, "breakpoint at {}", ip),
+        Stop::Breakpoint(Breakpoint::Memory(address)) => {
+            writeln!(writer, "breakpoint on [{}]", address)
+        }
+        Stop::Watchpoint(change) => writeln!(
+            writer,
+            "watchpoint: [{}] {} -> {}",
+            change.address, change.before, change.after
+        ),
+        Stop::NeedInput => writeln!(writer, "waiting for input"),
+        Stop::Halt => writeln!(writer, "halted"),
+        Stop::Invalid { address, value } => {
+            writeln!(writer, "{} at {} isn't an instruction", value, address)
+        }
+    }
+}
+
+/// Writes the instruction the machine is at.
+fn write_current(debugger: &Debugger, writer: &mut impl Write) -> io::Result<()> {
+    match debugger.current() {
+        Some(instruction) => writeln!(writer, "> {:>3}  {}", instruction.address, instruction),
+        None => writeln!(writer, "> {:>3}  ?", debugger.machine.ip),
+    }
+}
+
+/// Starts the debugger on stdin and stdout, with `analysis` showing where the
+/// program does its input and output first.
+pub fn start(program: &str) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+    let memory = Intcode::parse(program);
+
+    writeln!(writer, "{} cells; input/output at:", memory.len())?;
+
+    for site in analysis::io_sites(&memory) {
+        writeln!(writer, "{:>5}  {}", site.address, site)?;
+    }
+
+    repl(program, io::stdin().lock(), writer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // counts down from the input, outputting every number, and halts
+    const COUNTDOWN: &str = "3,12,4,12,1001,12,-1,12,1005,12,2,99,0";
+
+    #[test]
+    fn steps_and_traces() {
+        let mut debugger = Debugger::new(Intcode::from_input(COUNTDOWN), 3);
+        debugger.machine.input(2);
+
+        for _ in 0..5 {
+            assert_eq!(debugger.step(), None);
+        }
+
+        let trace: Vec<_> = debugger.trace.entries().map(Traced::to_string).collect();
+
+        assert_eq!(
+            trace,
+            [
+                "    4  add [12], -1, [12]           (2, -1) -> [12] = 1",
+                "    8  jnz [12], 2                  (1, 2)",
+                "    2  out [12]                     (1)",
+            ]
+        );
+        assert_eq!(debugger.outputs, [2, 1]);
+        assert_eq!(debugger.counters.total, 5);
+        assert_eq!(debugger.counters.by_opcode[&Opcode::Output], 2);
+    }
+
+    #[test]
+    fn stops_at_breakpoints_and_watchpoints() {
+        let mut debugger = Debugger::new(Intcode::from_input(COUNTDOWN), 0);
+        debugger.machine.input(3);
+
+        debugger.breakpoints.insert(Breakpoint::Ip(8));
+        assert_eq!(debugger.run(), Stop::Breakpoint(Breakpoint::Ip(8)));
+        assert_eq!(debugger.outputs, [3]);
+
+        debugger.breakpoints = BTreeSet::from([Breakpoint::Memory(12)]);
+        assert_eq!(debugger.run(), Stop::Breakpoint(Breakpoint::Memory(12)));
+        assert_eq!(debugger.machine.ip, 2);
+
+        debugger.breakpoints.clear();
+        debugger.watchpoints.insert(12);
+        assert_eq!(
+            debugger.run(),
+            Stop::Watchpoint(Change {
+                address: 12,
+                before: 2,
+                after: 1
+            })
+        );
+
+        debugger.watchpoints.clear();
+        assert_eq!(debugger.run(), Stop::Halt);
+        assert_eq!(debugger.outputs, [3, 2, 1]);
+        assert_eq!(debugger.trace.entries().len(), 0);
+    }
+
+    #[test]
+    fn stops_at_invalid_instructions() {
+        // jumps into the data after the halt
+        let mut debugger = Debugger::new(Intcode::from_input("1105,1,4,99,42"), 10);
+
+        let invalid = Stop::Invalid {
+            address: 4,
+            value: 42,
+        };
+
+        assert_eq!(debugger.run(), invalid);
+        assert_eq!(debugger.step(), Some(invalid));
+        assert_eq!(debugger.machine.ip, 4);
+    }
+
+    #[test]
+    fn runs_commands() {
+        let commands = "c\ni 2\nb 11\nc\nn\nq\ns\n";
+        let mut output = Vec::new();
+
+        repl(COUNTDOWN, commands.as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().collect();
+
+        assert_eq!(
+            lines,
+            [
+                "Intcode debugger; h for help",
+                ">   0  in [12]",
+                "waiting for input",
+                ">   0  in [12]",
+                "output: 2, 1",
+                "breakpoint at 11",
+                ">  11  hlt",
+                "7 instructions",
+                "  add  2",
+                "  in   1",
+                "  out  2",
+                "  jnz  2",
+                "  at     2: 2",
+                "  at     4: 2",
+                "  at     8: 2",
+                "  at     0: 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_memory() {
+        let commands = "i 2\nb 8\nc\nm\nd\n";
+        let mut output = Vec::new();
+
+        repl(COUNTDOWN, commands.as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<_> = output.lines().skip(5).collect();
+
+        assert_eq!(
+            lines,
+            [
+                "   12  0 -> 1",
+                "    0  in [12]",
+                "    2  out [12]",
+                "    4  add [12], -1, [12]",
+                "    8  jnz [12], 2",
+                "   11  hlt",
+                "   12  data 1",
+            ]
+        );
+    }
+}
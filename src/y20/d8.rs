@@ -1,97 +1,44 @@
 //! Auto-translated from the original Python solution by an LLM (Claude Code).
 //! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2020/tree/master/08
-use crate::util::Day;
+use crate::util::vm::handheld::{Handheld, ACC};
+use crate::util::vm::{Machine, Step};
+use crate::util::{Error, PartResult, Solution};
 use rustc_hash::FxHashSet;
 
 pub struct D8;
 
-fn parse(input: &str) -> Vec<(&str, i64)> {
-    input
-        .trim()
-        .lines()
-        .map(|line| {
-            let (opt, arg) = line.split_once(' ').unwrap();
-            (opt, arg.parse::<i64>().unwrap())
-        })
-        .collect()
-}
-
-impl Day for D8 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        let instructions = parse(input);
+/// Runs the boot code until it halts or is about to execute an instruction for the
+/// second time, returning whether it halted.
+fn run(machine: &mut Machine<Handheld>) -> bool {
+    let mut visited: FxHashSet<i64> = FxHashSet::default();
 
-        let mut acc: i64 = 0;
-        let mut ptr: i64 = 0;
-        let mut visited: FxHashSet<i64> = FxHashSet::default();
-
-        loop {
-            if visited.contains(&ptr) {
-                return Some(acc.to_string());
-            }
-            visited.insert(ptr);
+    machine.run_until(|machine| !visited.insert(machine.ip)) == Step::Halt
+}
 
-            let (opt, arg) = instructions[ptr as usize];
+impl Solution for D8 {
+    fn part1(&self, input: &str) -> PartResult {
+        let mut machine = Machine::<Handheld>::parse(input)?;
 
-            if opt == "acc" {
-                acc += arg;
-            } else if opt == "jmp" {
-                ptr += arg;
-                continue;
-            }
+        run(&mut machine);
 
-            ptr += 1;
-        }
+        Ok(machine.registers[ACC].into())
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
-        let instructions = parse(input);
-        let n = instructions.len();
-
-        for i in 0..n {
-            let mut acc: i64 = 0;
-            let mut ptr: i64 = 0;
-            let mut visited: FxHashSet<i64> = FxHashSet::default();
-
-            loop {
-                if ptr == n as i64 {
-                    return Some(acc.to_string());
-                }
-
-                if visited.contains(&ptr) {
-                    break;
-                }
-
-                visited.insert(ptr);
-
-                // A wrong flip can send ptr out of bounds (Python would wrap on a
-                // negative index); the correct flip terminates at ptr == n exactly,
-                // so any out-of-range ptr means this flip is invalid.
-                if ptr < 0 || ptr as usize >= n {
-                    break;
-                }
-
-                let (mut opt, arg) = instructions[ptr as usize];
-
-                if i as i64 == ptr {
-                    if opt == "jmp" {
-                        opt = "nop";
-                    } else {
-                        opt = "jmp";
-                    }
-                }
+    fn part2(&self, input: &str) -> PartResult {
+        let machine = Machine::<Handheld>::parse(input)?;
 
-                if opt == "acc" {
-                    acc += arg;
-                } else if opt == "jmp" {
-                    ptr += arg;
-                    continue;
-                }
+        for i in 0..machine.program.len() {
+            let mut repaired = machine.clone();
+            repaired.program[i] = repaired.program[i].repaired();
 
-                ptr += 1;
+            // a wrong repair can also jump out of the program somewhere other than
+            // its end, which doesn't count as terminating
+            if run(&mut repaired) && repaired.ip == machine.program.len() as i64 {
+                return Ok(repaired.registers[ACC].into());
             }
         }
 
-        None
+        Err(Error::Unsolvable("no repair terminates".to_string()))
     }
 }
@@ -3,7 +3,7 @@
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2021/tree/master/13
 use rustc_hash::FxHashSet;
 
-use crate::util::Day;
+use crate::util::{Answer, PartResult, Solution};
 
 pub struct D13;
 
@@ -44,8 +44,8 @@ fn apply_fold(dots: &mut [[i64; 2]], coord: char, val: i64) {
     }
 }
 
-impl Day for D13 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
+impl Solution for D13 {
+    fn part1(&self, input: &str) -> PartResult {
         let (mut dots, folds) = parse(input);
 
         let (coord, val) = folds[0];
@@ -53,10 +53,10 @@ impl Day for D13 {
 
         let set: FxHashSet<(i64, i64)> = dots.iter().map(|d| (d[0], d[1])).collect();
 
-        Some(set.len().to_string())
+        Ok(set.len().into())
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
+    fn part2(&self, input: &str) -> PartResult {
         let (mut dots, folds) = parse(input);
 
         for (coord, val) in folds {
@@ -82,6 +82,6 @@ impl Day for D13 {
             output.pop();
         }
 
-        Some(output)
+        Ok(Answer::from_art(output))
     }
 }
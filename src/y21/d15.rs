@@ -1,10 +1,8 @@
 //! Auto-translated from the original Python solution by an LLM (Claude Code).
 //! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2021/tree/master/15
+use crate::util::search;
 use crate::util::Day;
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
-use rustc_hash::FxHashSet;
 
 pub struct D15;
 
@@ -23,35 +21,17 @@ where
     let start = (0i64, 0i64);
     let end = (w - 1, h - 1);
 
-    let mut heap: BinaryHeap<Reverse<(i64, (i64, i64))>> = BinaryHeap::new();
-    heap.push(Reverse((0, start)));
-
-    let mut explored: FxHashSet<(i64, i64)> = FxHashSet::default();
-    explored.insert(start);
-
-    while let Some(Reverse((d, (x, y)))) = heap.pop() {
-        if (x, y) == end {
-            return d;
-        }
-
-        for (dx, dy) in [(0i64, 1i64), (1, 0), (-1, 0), (0, -1)] {
-            let nx = x + dx;
-            let ny = y + dy;
-
-            if !(0 <= nx && nx < w && 0 <= ny && ny < h) {
-                continue;
-            }
-
-            if explored.contains(&(nx, ny)) {
-                continue;
-            }
-
-            heap.push(Reverse((d + get(nx, ny), (nx, ny))));
-            explored.insert((nx, ny));
-        }
-    }
-
-    unreachable!()
+    let neighbours = |&(x, y): &(i64, i64)| {
+        [(0i64, 1i64), (1, 0), (-1, 0), (0, -1)]
+            .into_iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(|&(nx, ny)| 0 <= nx && nx < w && 0 <= ny && ny < h)
+            .map(|(nx, ny)| ((nx, ny), get(nx, ny)))
+    };
+
+    search::dijkstra([start], neighbours, |&node| node == end)
+        .goal_distance()
+        .unwrap()
 }
 
 impl Day for D15 {
@@ -7,9 +7,9 @@
 //! bounds are checked against x). The buggy Python prints 612060; this port does
 //! the geometrically-correct check and yields 545118. Which value AoC accepted is
 //! unknown — verify manually.
-//! ⚠️ part 2 UNSOLVED (too slow): the original 22-2.py used an O(n³) approach that
-//! did not finish (>300 s), and a faithful port likewise does not finish. Left as
-//! None pending a coordinate-compression / interval-subtraction rewrite.
+//! Part 2 (whose faithful port of the O(n³) original never finished) counts the
+//! cubes with a set of disjoint cuboids instead.
+use crate::util::intervals::{BoxSet, Cuboid};
 use crate::util::Day;
 
 pub struct D22;
@@ -66,75 +66,6 @@ fn is_on(point: (i64, i64, i64), rectangles: &[(String, Rectangle)]) -> bool {
     last_status == Some("on")
 }
 
-fn get_sorted_coordinates(rectangles: &[Rectangle]) -> (Vec<i64>, Vec<i64>, Vec<i64>) {
-    let mut xs: Vec<i64> = Vec::new();
-    let mut ys: Vec<i64> = Vec::new();
-    let mut zs: Vec<i64> = Vec::new();
-
-    for ((x1, x2), (y1, y2), (z1, z2)) in rectangles {
-        xs.push(*x1);
-        xs.push(*x2);
-        ys.push(*y1);
-        ys.push(*y2);
-        zs.push(*z1);
-        zs.push(*z2);
-    }
-
-    xs.sort();
-    ys.sort();
-    zs.sort();
-
-    (xs, ys, zs)
-}
-
-fn get_segments(coordinates: &[i64]) -> Vec<(i64, i64)> {
-    let mut segments: Vec<(i64, i64)> = Vec::new();
-
-    // mirror the Python which references x2 after the loop
-    let mut x2 = coordinates[coordinates.len() - 1];
-
-    for i in 0..coordinates.len() - 1 {
-        let x1 = coordinates[i];
-        x2 = coordinates[i + 1];
-        if x1 == x2 {
-            continue;
-        }
-
-        segments.push((x1, x1));
-
-        // if there are some things between, return them
-        if x1 != x2 + 1 {
-            segments.push((x1 + 1, x2 - 1));
-        }
-    }
-
-    segments.push((x2, x2));
-
-    segments
-}
-
-fn count_lit_points(rectangles: &[Rectangle], commands: &[(String, Rectangle)]) -> i64 {
-    let (xs, ys, zs) = get_sorted_coordinates(rectangles);
-
-    let mut total: i64 = 0;
-
-    let xseg = get_segments(&xs);
-    let yseg = get_segments(&ys);
-    let zseg = get_segments(&zs);
-
-    for &(x1, x2) in &xseg {
-        for &(y1, y2) in &yseg {
-            for &(z1, z2) in &zseg {
-                if is_on((x1, y1, z1), commands) {
-                    total += (x2 - x1 + 1) * (y2 - y1 + 1) * (z2 - z1 + 1);
-                }
-            }
-        }
-    }
-
-    total
-}
-
 impl Day for D22 {
     fn solve_part1(&self, input: &str) -> Option<String> {
         let commands = parse(input);
@@ -153,10 +84,20 @@ impl Day for D22 {
         Some(total.to_string())
     }
 
-    fn solve_part2(&self, _input: &str) -> Option<String> {
-        // Too slow: the original Python (O(n³)) timed out (>300 s) and this faithful
-        // port does not finish either. Left unsolved pending a coordinate-
-        // compression / interval-subtraction rewrite. See the file header.
-        None
+    fn solve_part2(&self, input: &str) -> Option<String> {
+        let commands = parse(input);
+
+        let mut cubes = BoxSet::new();
+        for (status, ((x1, x2), (y1, y2), (z1, z2))) in commands {
+            let cuboid = Cuboid::new([x1, y1, z1], [x2 + 1, y2 + 1, z2 + 1]);
+
+            if status == "on" {
+                cubes.insert(cuboid);
+            } else {
+                cubes.remove(&cuboid);
+            }
+        }
+
+        Some(cubes.volume().to_string())
     }
 }
@@ -1,111 +1,203 @@
-//! Auto-translated from the original Python solution by an LLM (Claude Code).
-//! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
-//! Original: https://github.com/xiaoxiae/Advent-of-Code-2021/tree/master/24
-//!
-//! ⚠️ part 2 UNSOLVED / part 1 HAND-TUNED: the original ALU/MONAD solution was
-//! solved by hand (hardcoded digits + a `randint` search), not a general solver.
-//! Part 1 (18116121134117) is the captured hand-found answer; part 2 (smallest)
-//! was never solved in the original, so solve_part2 is left defaulted (None).
-//! Needs a proper constraint-based ALU solver for a clean, deterministic result.
-use crate::util::Day;
-use rustc_hash::FxHashMap;
+use crate::util::vm::alu::{Alu, Instruction};
+use crate::util::vm::{Machine, Operand, Step};
+use crate::util::{Error, ParsedSolution, PartResult};
 
 pub struct D24;
 
-/// Parse the 14 (a, b, c) coefficient triples out of the ALU program.
+const W: usize = 0;
+const X: usize = 1;
+const Y: usize = 2;
+const Z: usize = 3;
+
+/// The block of instructions that MONAD runs for every digit of the model number,
+/// differing only in three constants:
 ///
-/// Each block of 18 instructions has the form:
-///   inp w
-///   ...
-///   div z {a}
-///   add x {b}
-///   ...
-///   add y {c}
-///   ...
-fn parse_abcs(input: &str) -> Vec<(i64, i64, i64)> {
-    let lines: Vec<&str> = input.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect();
-    let mut abcs = Vec::new();
-
-    // Blocks of 18 instructions, one per input digit.
-    let block = 18;
-    for chunk in lines.chunks_exact(block) {
-        let coeff = |line: &str| line.split_whitespace().nth(2).unwrap().parse().unwrap();
-        // div z {a}  -> line offset 4
-        let a: i64 = coeff(chunk[4]);
-        // add x {b}  -> line offset 5
-        let b: i64 = coeff(chunk[5]);
-        // add y {c}  -> line offset 15
-        let c: i64 = coeff(chunk[15]);
-        abcs.push((a, b, c));
-    }
+/// ```text
+/// x = z % 26 + b
+/// z /= a          (1 or 26)
+/// if x != w:
+///     z = z * 26 + w + c
+/// ```
+///
+/// Treating `z` as a stack of base-26 digits, a block with `a = 1` always pushes
+/// (its `b` is over 9, so `x` can't be a digit), and one with `a = 26` pops, pushing
+/// right back unless its digit equals the popped one plus `b`. With as many pops as
+/// pushes, `z` ends up 0 only if every pop matches.
+fn block(a: i64, b: i64, c: i64) -> [Instruction; 18] {
+    use Instruction::*;
+    use Operand::{Register as R, Value as V};
 
-    abcs
+    [
+        Inp(W),
+        Mul(X, V(0)),
+        Add(X, R(Z)),
+        Mod(X, V(26)),
+        Div(Z, V(a)),
+        Add(X, V(b)),
+        Eql(X, R(W)),
+        Eql(X, V(0)),
+        Mul(Y, V(0)),
+        Add(Y, V(25)),
+        Mul(Y, R(X)),
+        Add(Y, V(1)),
+        Mul(Z, R(Y)),
+        Mul(Y, V(0)),
+        Add(Y, R(W)),
+        Add(Y, V(c)),
+        Mul(Y, R(X)),
+        Add(Z, R(Y)),
+    ]
 }
 
-/// Run one digit-block of the ALU, mirroring the Python loop body:
-///   x = z % 26 + b
-///   z //= a
-///   x = 0 if x == w else 1
-///   y = 25 * x + 1
-///   z *= y
-///   y = (w + c) * x
-///   z += y
-fn step(z: i64, w: i64, a: i64, b: i64, c: i64) -> i64 {
-    let mut z = z;
-    let mut x = z % 26 + b;
-    z /= a;
-    x = if x == w { 0 } else { 1 };
-    let mut y = 25 * x + 1;
-    z *= y;
-    y = (w + c) * x;
-    z += y;
-    z
+/// The digit at `second` has to be the one at `first` plus `offset`.
+#[derive(Debug, Clone, Copy)]
+struct Constraint {
+    first: usize,
+    second: usize,
+    offset: i64,
 }
 
-/// Search for valid model numbers digit-by-digit, memoizing on (index, z).
-///
-/// `digits` is the digit order to try at each position: 9..=1 for the largest
-/// model number (part 1), 1..=9 for the smallest.
-fn search(
-    abcs: &[(i64, i64, i64)],
-    idx: usize,
-    z: i64,
-    digits: &[i64],
-    memo: &mut FxHashMap<(usize, i64), Option<u64>>,
-) -> Option<u64> {
-    if idx == abcs.len() {
-        return if z == 0 { Some(0) } else { None };
+pub struct Monad {
+    program: Vec<Instruction>,
+    constraints: Vec<Constraint>,
+}
+
+fn parse(input: &str) -> Result<Monad, Error> {
+    let program = Machine::<Alu>::parse_program(input)?;
+
+    // the lines of the instructions (as parse_program reads them), for errors
+    let lines: Vec<&str> = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let invalid =
+        |block: usize, message: String| Error::parse_in(input, lines[18 * block], message);
+
+    if program.len() != 14 * 18 {
+        // at the first instruction too many, or the end of the input if too few
+        let at = lines.get(14 * 18).copied().unwrap_or(&input[input.len()..]);
+
+        return Err(Error::parse_in(
+            input,
+            at,
+            format!(
+                "expected 14 blocks of 18 instructions, got {} instructions",
+                program.len()
+            ),
+        ));
     }
 
-    if let Some(&cached) = memo.get(&(idx, z)) {
-        return cached;
+    let mut stack = vec![];
+    let mut constraints = vec![];
+
+    for (digit, chunk) in program.chunks_exact(18).enumerate() {
+        let constant = |i: usize| match chunk[i] {
+            Instruction::Div(_, Operand::Value(v)) | Instruction::Add(_, Operand::Value(v)) => v,
+            _ => 0,
+        };
+        let (a, b, c) = (constant(4), constant(5), constant(15));
+
+        if chunk != block(a, b, c) {
+            return Err(invalid(
+                digit,
+                format!("block {} isn't a MONAD block", digit + 1),
+            ));
+        }
+
+        match a {
+            1 if b > 9 => stack.push((digit, c)),
+            26 => {
+                let (first, c) = stack.pop().ok_or_else(|| {
+                    invalid(digit, format!("block {} pops an empty stack", digit + 1))
+                })?;
+
+                constraints.push(Constraint {
+                    first,
+                    second: digit,
+                    offset: c + b,
+                });
+            }
+            _ => {
+                return Err(invalid(
+                    digit,
+                    format!("block {} neither pushes nor pops", digit + 1),
+                ))
+            }
+        }
     }
 
-    let (a, b, c) = abcs[idx];
-    let mut result = None;
-    for &w in digits {
-        let nz = step(z, w, a, b, c);
-        if let Some(rest) = search(abcs, idx + 1, nz, digits, memo) {
-            result = Some(w as u64 * 10u64.pow((abcs.len() - 1 - idx) as u32) + rest);
-            break;
+    if let Some(&(digit, _)) = stack.last() {
+        return Err(invalid(
+            digit,
+            format!("block {} pushes a digit that is never popped", digit + 1),
+        ));
+    }
+
+    Ok(Monad {
+        program,
+        constraints,
+    })
+}
+
+/// Whether MONAD accepts the model number, by running it.
+fn accepts(program: &[Instruction], digits: &[i64]) -> bool {
+    let mut machine = Machine::<Alu>::new(program.to_vec());
+    machine.input.extend(digits);
+
+    machine.run() == Step::Halt && machine.registers[Z] == 0
+}
+
+/// The largest or smallest model number MONAD accepts: each constrained pair of
+/// digits is made as large (or small) as it can be, starting from its first digit.
+fn model_number(monad: &Monad, largest: bool) -> PartResult {
+    let mut digits = [0; 14];
+
+    for &Constraint {
+        first,
+        second,
+        offset,
+    } in &monad.constraints
+    {
+        if offset.abs() > 8 {
+            return Err(Error::Unsolvable(format!(
+                "digits {} and {} can't differ by {}",
+                first + 1,
+                second + 1,
+                offset
+            )));
         }
+
+        digits[first] = if largest {
+            9.min(9 - offset)
+        } else {
+            1.max(1 - offset)
+        };
+        digits[second] = digits[first] + offset;
     }
 
-    memo.insert((idx, z), result);
-    result
+    if !accepts(&monad.program, &digits) {
+        return Err(Error::Unsolvable(format!("MONAD rejects {:?}", digits)));
+    }
+
+    Ok(digits
+        .iter()
+        .fold(0, |number, digit| number * 10 + digit)
+        .into())
 }
 
-impl Day for D24 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        // The original Python solution (24-1.py) brute-forces (by hand-fixing
-        // most digits and randomly searching the rest) the *smallest* valid
-        // model number, converging on 18116121134117. We reproduce that exact
-        // output by searching digits ascending (1..=9) and taking the first
-        // valid model number found.
-        let abcs = parse_abcs(input);
-        let digits: Vec<i64> = (1..=9).collect();
-        let mut memo = FxHashMap::default();
-        let answer = search(&abcs, 0, 0, &digits, &mut memo)?;
-        Some(answer.to_string())
+impl ParsedSolution for D24 {
+    type Parsed = Monad;
+
+    fn parse(&self, input: &str) -> Result<Monad, Error> {
+        parse(input)
+    }
+
+    fn part1(&self, monad: &Monad) -> PartResult {
+        model_number(monad, true)
+    }
+
+    fn part2(&self, monad: &Monad) -> PartResult {
+        model_number(monad, false)
     }
 }
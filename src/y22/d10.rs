@@ -1,87 +1,48 @@
-use crate::util::Day;
+use crate::util::vm::crt::{self, X};
+use crate::util::{Answer, PartResult, Solution};
 
 pub struct D10;
 
-pub enum Instruction {
-    Noop,
-    Add(isize),
-}
-
-
-impl Day for D10 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        let cycles = vec![20, 60, 100, 140, 180, 220];
+impl Solution for D10 {
+    fn part1(&self, input: &str) -> PartResult {
+        let cycles = [20, 60, 100, 140, 180, 220];
         let mut strength = 0;
-        let mut x = 1;
-        let mut instructions: Vec<Instruction> = vec![Instruction::Noop];
-
-        for line in input.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            match parts[0] {
-                "noop" => {
-                    instructions.push(Instruction::Noop);
-                }
-                "addx" => {
-                    instructions.push(Instruction::Noop);
-                    instructions.push(Instruction::Add(parts[1].parse::<isize>().unwrap()));
-                }
-                _ => panic!("Unknown instruction: {}", parts[0]),
-            }
-        }
+        let mut machine = crt::parse(input)?;
 
-        for (cycle, instruction) in instructions.iter().enumerate() {
-            let cycle = cycle as isize;
+        // the machine has executed `steps` cycles, so it is during the next one
+        machine.run_until(|machine| {
+            let cycle = machine.steps as i64 + 1;
 
             if cycles.contains(&cycle) {
-                strength += cycle * x;
+                strength += cycle * machine.registers[X];
             }
 
+            false
+        });
 
-            if let Instruction::Add(val) = instruction {
-                x += val;
-            }
-        }
-
-        Option::from(strength.to_string())
+        Ok(strength.into())
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
+    fn part2(&self, input: &str) -> PartResult {
         let mut crt = String::new();
-        let mut x: isize = 1;
-        let mut instructions: Vec<Instruction> = Vec::new();
+        let mut machine = crt::parse(input)?;
 
-        for line in input.lines() {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            match parts[0] {
-                "noop" => {
-                    instructions.push(Instruction::Noop);
-                }
-                "addx" => {
-                    instructions.push(Instruction::Noop);
-                    instructions.push(Instruction::Add(parts[1].parse::<isize>().unwrap()));
-                }
-                _ => panic!("Unknown instruction: {}", parts[0]),
-            }
-        }
+        machine.run_until(|machine| {
+            let position = machine.steps as i64 % 40;
 
-        for (cycle, instruction) in instructions.iter().enumerate() {
-            let cycle = cycle as isize;
-
-            if cycle % 40 == 0 {
+            if position == 0 && machine.steps != 0 {
                 crt.push('\n');
             }
 
-            if (cycle % 40 == x - 1) || (cycle % 40 == x) || (cycle % 40 == x + 1) {
+            if (machine.registers[X] - position).abs() <= 1 {
                 crt.push('#');
             } else {
                 crt.push('.');
             }
 
-            if let Instruction::Add(val) = instruction {
-                x += val;
-            }
-        }
+            false
+        });
 
-        Option::from(crt)
+        Ok(Answer::from_art(crt))
     }
 }
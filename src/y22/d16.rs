@@ -4,15 +4,17 @@
 //!
 //! ⚠️ SLOW (~13s, part 2): faithful port of the subset-enumeration BFS (32k valve
 //! subsets). Correct (1850 / 2306) but flagged for manual optimization.
-use crate::util::Day;
+use crate::util::{Error, ParsedSolution, PartResult};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::VecDeque;
 
 pub struct D16;
 
+type Valves = FxHashMap<String, (i64, Vec<String>)>;
+
 /// Parse the input into a map: valve name -> (flow, neighbours).
-fn parse(input: &str) -> FxHashMap<String, (i64, Vec<String>)> {
-    let mut valves: FxHashMap<String, (i64, Vec<String>)> = FxHashMap::default();
+fn parse(input: &str) -> Result<Valves, Error> {
+    let mut valves: Valves = FxHashMap::default();
 
     for row in input.lines() {
         let row = row.trim_end_matches('\n');
@@ -22,27 +24,31 @@ fn parse(input: &str) -> FxHashMap<String, (i64, Vec<String>)> {
 
         // Python: row.split(maxsplit=9)
         let mut parts: Vec<&str> = row.splitn(10, ' ').collect();
+        if parts.len() < 10 {
+            return Err(Error::parse_in(input, row, "expected a valve description"));
+        }
+
         // The last element may contain leading whitespace removed by python split;
         // splitn keeps the rest intact (no extra leading space here since fields are single-spaced).
         let valve = parts[1].to_string();
         // parts[4] is like "rate=22;" -> [5:-1] gives "22"
         let flow_field = parts[4];
-        let flow_str = &flow_field[5..flow_field.len() - 1];
-        let flow: i64 = flow_str.parse().unwrap();
+        let flow: i64 = flow_field
+            .strip_prefix("rate=")
+            .and_then(|flow_str| flow_str.strip_suffix(';'))
+            .and_then(|flow_str| flow_str.parse().ok())
+            .ok_or_else(|| Error::parse_in(input, flow_field, "expected a flow rate"))?;
         let last = parts.pop().unwrap();
         let leads_to: Vec<String> = last.split(", ").map(|s| s.to_string()).collect();
 
         valves.insert(valve, (flow, leads_to));
     }
 
-    valves
+    Ok(valves)
 }
 
 /// BFS distances from `valve` to all other valves.
-fn bfs_distances(
-    valves: &FxHashMap<String, (i64, Vec<String>)>,
-    valve: &str,
-) -> FxHashMap<String, i64> {
+fn bfs_distances(valves: &Valves, valve: &str) -> FxHashMap<String, i64> {
     let mut visited: FxHashSet<String> = FxHashSet::default();
     let mut queue: VecDeque<(String, i64)> = VecDeque::new();
     queue.push_back((valve.to_string(), 0));
@@ -63,14 +69,18 @@ fn bfs_distances(
     paths
 }
 
-impl Day for D16 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        let valves = parse(input);
+impl ParsedSolution for D16 {
+    type Parsed = Valves;
 
+    fn parse(&self, input: &str) -> Result<Valves, Error> {
+        parse(input)
+    }
+
+    fn part1(&self, valves: &Valves) -> PartResult {
         // precompute distances from every valve
         let mut valve_paths: FxHashMap<String, FxHashMap<String, i64>> = FxHashMap::default();
         for valve in valves.keys() {
-            valve_paths.insert(valve.clone(), bfs_distances(&valves, valve));
+            valve_paths.insert(valve.clone(), bfs_distances(valves, valve));
         }
 
         // BFS over states: (remaining, current, opened (sorted tuple), pressure)
@@ -98,12 +108,10 @@ impl Day for D16 {
             }
         }
 
-        Some(max_pressure.to_string())
+        Ok(max_pressure.into())
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
-        let valves = parse(input);
-
+    fn part2(&self, valves: &Valves) -> PartResult {
         // interesting (non-zero) valves, sorted
         let mut non_zero_valves: Vec<String> = valves
             .iter()
@@ -206,6 +214,6 @@ impl Day for D16 {
             max_pressure = max_pressure.max(a + b);
         }
 
-        Some(max_pressure.to_string())
+        Ok(max_pressure.into())
     }
 }
@@ -1,17 +1,10 @@
-//! Auto-translated from the original Python solution by an LLM (Claude Code).
-//! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
-//! Original: https://github.com/xiaoxiae/Advent-of-Code-2022/tree/master/22
-//!
-//! ⚠️ part 2 UNSOLVED: the original 22-2.py crashed (IndexError) on the real input
-//! — the cube-folding was never completed. solve_part2 returns None. Part 1 (36518)
-//! is verified.
-use crate::util::Day;
+use crate::util::{Error, PartResult, Solution};
 use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::VecDeque;
 
 pub struct D22;
 
 const DELTAS: [(i64, i64); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
-const DELTAS_DIAGONAL: [(i64, i64); 4] = [(1, 1), (1, -1), (-1, -1), (-1, 1)];
 
 /// Parse input into (field as padded rows of chars, moves, turns, start_x).
 fn parse(input: &str) -> (Vec<Vec<char>>, Vec<i64>, Vec<char>, i64) {
@@ -59,364 +52,253 @@ fn parse(input: &str) -> (Vec<Vec<char>>, Vec<i64>, Vec<char>, i64) {
     (field, moves, turns, x_start)
 }
 
+/// The tile at a position, or a space outside of the field.
 fn at(field: &[Vec<char>], x: i64, y: i64) -> char {
-    field[y as usize][x as usize]
+    usize::try_from(y)
+        .ok()
+        .and_then(|y| field.get(y))
+        .zip(usize::try_from(x).ok())
+        .and_then(|(row, x)| row.get(x).copied())
+        .unwrap_or(' ')
 }
 
 fn rotate(orientation: i64, where_: char) -> i64 {
     (orientation + (if where_ == 'R' { 1 } else { -1 })).rem_euclid(DELTAS.len() as i64)
 }
 
-impl Day for D22 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        let (field, moves, turns, x_start) = parse(input);
-        let height = field.len() as i64;
-
-        let move_and_wrap = |x: i64, y: i64, delta: (i64, i64)| -> (i64, i64) {
-            let mut x = x;
-            let mut y = y;
-            loop {
-                y = (y + delta.1).rem_euclid(height);
-                let w = field[y as usize].len() as i64;
-                x = (x + delta.0).rem_euclid(w);
-                if field[y as usize][x as usize] != ' ' {
-                    return (x, y);
-                }
-            }
-        };
+/// Follows the path, with `step` giving the position and orientation one step on
+/// (which is taken unless it's a wall), and returns the password.
+fn walk(input: &str, step: impl Fn(i64, i64, i64) -> State) -> i64 {
+    let (field, moves, turns, x_start) = parse(input);
 
-        let move_forward = |x: i64, y: i64, orientation: i64, distance: i64| -> (i64, i64) {
-            if distance == 0 {
-                return (x, y);
-            }
-            let (dx, dy) = DELTAS[orientation as usize];
-            let mut x = x;
-            let mut y = y;
-            for _ in 0..distance {
-                let (nx, ny) = move_and_wrap(x, y, (dx, dy));
-                if at(&field, nx, ny) != '.' {
-                    return (x, y);
-                }
-                x = nx;
-                y = ny;
-            }
-            (x, y)
-        };
+    let (mut x, mut y, mut orientation) = (x_start, 0, 0);
 
-        let mut orientation = 0i64;
-        let mut x = x_start;
-        let mut y = 0i64;
+    for (i, &m) in moves.iter().enumerate() {
+        for _ in 0..m {
+            let (nx, ny, no) = step(x, y, orientation);
 
-        for (i, &m) in moves.iter().enumerate() {
-            let (nx, ny) = move_forward(x, y, orientation, m);
-            x = nx;
-            y = ny;
-            if i < turns.len() {
-                orientation = rotate(orientation, turns[i]);
+            if at(&field, nx, ny) != '.' {
+                break;
             }
+
+            (x, y, orientation) = (nx, ny, no);
         }
 
-        let answer = 1000 * (y + 1) + 4 * (x + 1) + orientation;
-        Some(answer.to_string())
+        if i < turns.len() {
+            orientation = rotate(orientation, turns[i]);
+        }
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
-        let (field, moves, turns, x_start) = parse(input);
-        let width = field.iter().map(|l| l.len()).max().unwrap_or(0) as i64;
-        let height = field.len() as i64;
+    1000 * (y + 1) + 4 * (x + 1) + orientation
+}
 
-        let side_length = ((width * height / 12) as f64).sqrt() as i64;
+/// A position and an orientation (an index into `DELTAS`).
+type State = (i64, i64, i64);
 
-        let y_start = 0i64;
+type Vector = [i64; 3];
 
-        let in_bounds = |nx: i64, ny: i64| -> bool { 0 <= nx && nx < width && 0 <= ny && ny < height };
+fn neg(v: Vector) -> Vector {
+    v.map(|c| -c)
+}
 
-        // find the points on the border of the field (flood fill from start)
-        let mut queue: std::collections::VecDeque<(i64, i64)> = std::collections::VecDeque::new();
-        queue.push_back((x_start, y_start));
-        let mut field_points: FxHashSet<(i64, i64)> = FxHashSet::default();
-        field_points.insert((x_start, y_start));
-        while let Some((cx, cy)) = queue.pop_front() {
-            for &(dx, dy) in DELTAS.iter() {
-                let (nx, ny) = (cx + dx, cy + dy);
-                if !in_bounds(nx, ny) {
-                    continue;
-                }
-                if at(&field, nx, ny) == ' ' {
-                    continue;
-                }
-                if !field_points.contains(&(nx, ny)) {
-                    field_points.insert((nx, ny));
-                    queue.push_back((nx, ny));
-                }
-            }
-        }
+fn dot(u: Vector, v: Vector) -> i64 {
+    u.iter().zip(v).map(|(a, b)| a * b).sum()
+}
 
-        // filter inner points
-        let mut border_points: FxHashSet<(i64, i64)> = FxHashSet::default();
-        for &(cx, cy) in field_points.iter() {
-            let mut neighbouring = 0;
-            for &(dx, dy) in DELTAS.iter() {
-                if field_points.contains(&(cx + dx, cy + dy)) {
-                    neighbouring += 1;
-                }
-            }
-            if neighbouring != 4 {
-                border_points.insert((cx, cy));
-            }
-        }
+/// A face of the cube: where it is in the field, and how it sits in space once
+/// folded, as its outward normal and the directions its `x` and `y` run in.
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    x: i64,
+    y: i64,
+    normal: Vector,
+    right: Vector,
+    down: Vector,
+}
 
-        // order them by their adjacency (start from (cx, cy) = last popped in flood fill;
-        // matches Python which uses the leftover loop variable cx, cy).
-        // In Python, cx, cy carry the last values from the flood-fill loop.
-        // Reconstruct: the last popped node. We track it during flood fill above is gone;
-        // re-run minimal: but Python's queue empties so cx,cy = last popped element.
-        // We replicate by repeating the flood fill to capture the final (cx, cy).
-        let mut last_cx = x_start;
-        let mut last_cy = y_start;
-        {
-            let mut q2: std::collections::VecDeque<(i64, i64)> = std::collections::VecDeque::new();
-            q2.push_back((x_start, y_start));
-            let mut seen: FxHashSet<(i64, i64)> = FxHashSet::default();
-            seen.insert((x_start, y_start));
-            while let Some((cx, cy)) = q2.pop_front() {
-                last_cx = cx;
-                last_cy = cy;
-                for &(dx, dy) in DELTAS.iter() {
-                    let (nx, ny) = (cx + dx, cy + dy);
-                    if !in_bounds(nx, ny) {
-                        continue;
-                    }
-                    if at(&field, nx, ny) == ' ' {
-                        continue;
-                    }
-                    if !seen.contains(&(nx, ny)) {
-                        seen.insert((nx, ny));
-                        q2.push_back((nx, ny));
-                    }
-                }
-            }
-        }
+impl Face {
+    /// The direction in space of an orientation (an index into `DELTAS`) on the face.
+    fn direction(&self, orientation: usize) -> Vector {
+        [self.right, self.down, neg(self.right), neg(self.down)][orientation]
+    }
 
-        let deltas_plus_diag: Vec<(i64, i64)> = [DELTAS, DELTAS_DIAGONAL].concat();
-
-        let mut bqueue: std::collections::VecDeque<(i64, i64)> = std::collections::VecDeque::new();
-        bqueue.push_back((last_cx, last_cy));
-        let mut sorted_border_points: Vec<(i64, i64)> = Vec::new();
-        let mut sorted_set: FxHashSet<(i64, i64)> = FxHashSet::default();
-        while let Some((cx, cy)) = bqueue.pop_front() {
-            sorted_border_points.push((cx, cy));
-            sorted_set.insert((cx, cy));
-            for &(dx, dy) in deltas_plus_diag.iter() {
-                let (nx, ny) = (cx + dx, cy + dy);
-                if border_points.contains(&(nx, ny)) && !sorted_set.contains(&(nx, ny)) {
-                    bqueue.push_back((nx, ny));
-                    break;
-                }
-            }
-        }
+    /// The face next to this one in the net in a direction, folded over that edge.
+    fn fold(&self, orientation: usize, size: i64) -> Face {
+        let (dx, dy) = DELTAS[orientation];
+        let edge = self.direction(orientation);
+
+        // the face's normal turns to point over the edge, and the direction that
+        // pointed over it now points down into the cube
+        let turn = |v: Vector| match v {
+            v if v == edge => neg(self.normal),
+            v if v == neg(edge) => self.normal,
+            v => v,
+        };
 
-        // build the border list of (x, y, orientation)
-        let mut border: Vec<(i64, i64, i64)> = Vec::new();
-        for &(cx, cy) in sorted_border_points.iter() {
-            for (o, &(dx, dy)) in DELTAS.iter().enumerate() {
-                let (nx, ny) = (cx + dx, cy + dy);
-                if !in_bounds(nx, ny) || at(&field, nx, ny) == ' ' {
-                    border.push((cx, cy, o as i64));
-                    continue;
-                }
-            }
+        Face {
+            x: self.x + dx * size,
+            y: self.y + dy * size,
+            normal: edge,
+            right: turn(self.right),
+            down: turn(self.down),
         }
+    }
+}
 
-        let blen = border.len() as i64;
-        // Guard: original Python crashes (IndexError) on inputs where this logic
-        // goes out of range. Replicate negative-index semantics where possible; if
-        // an access would be out of bounds, mirror the Python crash by bailing.
-        let bidx = |i: i64| -> Option<usize> {
-            if blen == 0 {
-                return None;
-            }
-            let m = i.rem_euclid(blen);
-            Some(m as usize)
-        };
+/// Folds the field into a cube and returns where each step off the edge of a face
+/// (that doesn't lead onto the face next to it in the net) ends up, for any of the
+/// eleven nets.
+fn fold(field: &[Vec<char>]) -> Result<FxHashMap<State, State>, Error> {
+    let tiles = field.iter().flatten().filter(|&&c| c != ' ').count() as i64;
+    let size = (tiles / 6).isqrt();
+
+    if size == 0 || 6 * size * size != tiles {
+        return Err(Error::Unsolvable(format!(
+            "{} tiles can't be a cube",
+            tiles
+        )));
+    }
 
-        // fix incorrectly oriented corners
-        // for i in range(-3, len(border)): uses border[i+j] for j in -3..=0
-        {
-            let mut i = -3i64;
-            while i < blen {
-                // Python indexes border[i+j]; negative indices wrap, positive >= len crash.
-                let mut vals = [0i64; 4];
-                let mut ok = true;
-                for (k, j) in (-3..=0).enumerate() {
-                    let idx = i + j;
-                    if idx >= blen {
-                        ok = false;
-                        break;
-                    }
-                    match bidx(idx) {
-                        Some(u) => vals[k] = border[u].2,
-                        None => {
-                            ok = false;
-                            break;
-                        }
-                    }
-                }
-                if !ok {
-                    // Python would raise IndexError here; abort part 2 like the original.
-                    return None;
-                }
-                let (o1, o2, o3, o4) = (vals[0], vals[1], vals[2], vals[3]);
-                if o1 == o3 && o2 == o4 && o1 != o2 && o2 != o3 && o3 != o4 {
-                    // swap border[i-2] and border[i-1]
-                    let ia = bidx(i - 2);
-                    let ib = bidx(i - 1);
-                    if let (Some(a), Some(b)) = (ia, ib) {
-                        border.swap(a, b);
-                    }
-                }
-                i += 1;
+    let is_face = |x: i64, y: i64| at(field, x, y) != ' ';
+
+    let first = (0..)
+        .step_by(size as usize)
+        .take_while(|&x| x < field[0].len() as i64)
+        .find(|&x| is_face(x, 0))
+        .ok_or_else(|| Error::Unsolvable("no face in the first row".to_string()))?;
+
+    // place the faces by folding over the edges of the net, starting from the first
+    let mut faces = vec![Face {
+        x: first,
+        y: 0,
+        normal: [0, 0, -1],
+        right: [1, 0, 0],
+        down: [0, 1, 0],
+    }];
+    let mut queue = VecDeque::from([faces[0]]);
+
+    while let Some(face) = queue.pop_front() {
+        for orientation in 0..4 {
+            let next = face.fold(orientation, size);
+
+            if is_face(next.x, next.y) && !faces.iter().any(|f| (f.x, f.y) == (next.x, next.y)) {
+                faces.push(next);
+                queue.push_back(next);
             }
         }
+    }
 
-        let is_edge_point = |x: i64, y: i64| -> Option<(i64, i64)> {
-            let mut air_count = 0;
-            let mut wall_count = 0;
-            let mut d: Option<(i64, i64)> = None;
-            for &(dx, dy) in DELTAS_DIAGONAL.iter() {
-                if at(&field, x + dx, y + dy) == ' ' {
-                    air_count += 1;
-                    d = Some((dx, dy));
-                }
-            }
-            for &(dx, dy) in DELTAS.iter() {
-                if at(&field, x + dx, y + dy) != ' ' {
-                    wall_count += 1;
-                }
-            }
-            if air_count == 1 && wall_count == 4 {
-                d
-            } else {
-                None
-            }
-        };
+    if faces.len() != 6 {
+        return Err(Error::Unsolvable(format!(
+            "the net has {} faces of size {}",
+            faces.len(),
+            size
+        )));
+    }
 
-        let deltas_index = |d: (i64, i64)| -> i64 {
-            DELTAS.iter().position(|&v| v == d).unwrap() as i64
-        };
+    // six squares that don't fold into a cube (like six in a row) overlap somewhere
+    let normals: FxHashSet<Vector> = faces.iter().map(|face| face.normal).collect();
 
-        // portals: (x, y, orientation) -> (x, y, orientation)
-        let mut portals: FxHashMap<(i64, i64, i64), (i64, i64, i64)> = FxHashMap::default();
-
-        // create edge point portals
-        let mut corner: Vec<(Vec<(i64, i64, i64)>, Vec<(i64, i64, i64)>)> = Vec::new();
-        for y in 1..(height - 1) {
-            for x in 1..(width - 1) {
-                if let Some(d) = is_edge_point(x, y) {
-                    let mut a_side = Vec::new();
-                    let mut b_side = Vec::new();
-                    for i in 1..=side_length {
-                        let a_in = (x + d.0 * i, y, deltas_index((0, d.1)));
-                        let b_in = (x, y + d.1 * i, deltas_index((d.0, 0)));
-                        let a_out = (b_in.0, b_in.1, (b_in.2 + 2).rem_euclid(DELTAS.len() as i64));
-                        let b_out = (a_in.0, a_in.1, (a_in.2 + 2).rem_euclid(DELTAS.len() as i64));
-                        portals.insert(a_in, a_out);
-                        portals.insert(b_in, b_out);
-                        a_side.push(a_in);
-                        b_side.push(b_in);
-                    }
-                    corner.push((a_side, b_side));
-                }
-            }
-        }
+    if normals.len() != 6 {
+        return Err(Error::Unsolvable(
+            "the net doesn't fold into a cube".to_string(),
+        ));
+    }
 
-        let move_and_wrap = |x: i64, y: i64, orientation: i64,
-                             portals: &FxHashMap<(i64, i64, i64), (i64, i64, i64)>|
-         -> (i64, i64, i64) {
-            if let Some(&p) = portals.get(&(x, y, orientation)) {
-                return p;
-            }
-            let (dx, dy) = DELTAS[orientation as usize];
-            let (mut nx, mut ny) = (x + dx, y + dy);
-            if !field_points.contains(&(nx, ny)) {
-                nx = x;
-                ny = y;
-            }
-            (nx, ny, orientation)
-        };
+    // positions on the cube are the centers of the tiles, in coordinates doubled so
+    // that they are whole: a face spans -size..size and sits at `size` along its normal
+    let mut wraps = FxHashMap::default();
 
-        // walk from each side of the corner and wherever you end up, those two wrap
-        for (a_side, b_side) in corner.iter() {
-            for (a0, b0) in a_side.iter().zip(b_side.iter()) {
-                let mut a = *a0;
-                let mut b = *b0;
-                for _ in 0..(side_length * 4) {
-                    a = move_and_wrap(a.0, a.1, a.2, &portals);
-                    b = move_and_wrap(b.0, b.1, b.2, &portals);
-                }
-                portals.insert(a, (b.0, b.1, (b.2 + 2).rem_euclid(DELTAS.len() as i64)));
-                portals.insert(b, (a.0, a.1, (a.2 + 2).rem_euclid(DELTAS.len() as i64)));
-            }
-        }
+    for face in &faces {
+        for (orientation, (dx, dy)) in DELTAS.into_iter().enumerate() {
+            let edge = face.direction(orientation);
 
-        // add the one remaining side
-        let mut remaining: Vec<usize> = Vec::new();
-        for (i, b) in border.iter().enumerate() {
-            if !portals.contains_key(b) {
-                remaining.push(i);
-            }
-        }
+            for i in 0..size {
+                // the tiles along the edge, from the face's corner in the direction
+                let (x, y) = match orientation {
+                    0 => (face.x + size - 1, face.y + i),
+                    1 => (face.x + i, face.y + size - 1),
+                    2 => (face.x, face.y + i),
+                    _ => (face.x + i, face.y),
+                };
 
-        for j in 0..2i64 {
-            for ii in 0..side_length {
-                let i = ii + j * side_length * 2;
-                let idx1 = i as usize;
-                let idx2 = (side_length * 2 - i - 1) as usize;
-                if idx1 >= remaining.len() || idx2 >= remaining.len() {
-                    return None;
+                if is_face(x + dx, y + dy) {
+                    continue;
                 }
-                let o1 = border[remaining[idx1]];
-                let o2 = border[remaining[idx2]];
-                portals.insert(o1, (o2.0, o2.1, (o2.2 + 2).rem_euclid(DELTAS.len() as i64)));
-                portals.insert(o2, (o1.0, o1.1, (o1.2 + 2).rem_euclid(DELTAS.len() as i64)));
+
+                let local = |v: i64| 2 * v + 1 - size;
+
+                // step over the edge: half a tile out along it and half down the normal
+                let point = [0, 1, 2].map(|c| {
+                    face.normal[c] * size
+                        + face.right[c] * local(x - face.x)
+                        + face.down[c] * local(y - face.y)
+                        + edge[c]
+                        - face.normal[c]
+                });
+
+                // every side of the cube has a face, and the normal of the one left is
+                // one of the directions on the one entered
+                let next = faces
+                    .iter()
+                    .find(|f| f.normal == edge)
+                    .expect("a side is missing");
+                let unlocal = |v: i64| (v + size - 1) / 2;
+                let turned = (0..4)
+                    .find(|&o| next.direction(o) == neg(face.normal))
+                    .expect("the faces are askew");
+
+                wraps.insert(
+                    (x, y, orientation as i64),
+                    (
+                        next.x + unlocal(dot(point, next.right)),
+                        next.y + unlocal(dot(point, next.down)),
+                        turned as i64,
+                    ),
+                );
             }
         }
+    }
 
-        let move_forward = |x: i64, y: i64, orientation: i64, distance: i64,
-                            portals: &FxHashMap<(i64, i64, i64), (i64, i64, i64)>|
-         -> (i64, i64, i64) {
-            if distance == 0 {
-                return (x, y, orientation);
-            }
-            let mut x = x;
-            let mut y = y;
-            let mut orientation = orientation;
-            for _ in 0..distance {
-                let (nx, ny, norientation) = move_and_wrap(x, y, orientation, portals);
-                if at(&field, nx, ny) != '.' {
+    Ok(wraps)
+}
+
+impl Solution for D22 {
+    fn part1(&self, input: &str) -> PartResult {
+        let (field, _, _, _) = parse(input);
+        let height = field.len() as i64;
+
+        // step on, wrapping around to the other side of the row or column
+        let step = |x: i64, y: i64, orientation: i64| {
+            let (dx, dy) = DELTAS[orientation as usize];
+            let (mut x, mut y) = (x, y);
+
+            loop {
+                y = (y + dy).rem_euclid(height);
+                x = (x + dx).rem_euclid(field[y as usize].len() as i64);
+
+                if field[y as usize][x as usize] != ' ' {
                     return (x, y, orientation);
                 }
-                x = nx;
-                y = ny;
-                orientation = norientation;
             }
-            (x, y, orientation)
         };
 
-        let mut x = x_start;
-        let mut y = y_start;
-        let mut orientation = 0i64;
-        for (i, &m) in moves.iter().enumerate() {
-            let (nx, ny, no) = move_forward(x, y, orientation, m, &portals);
-            x = nx;
-            y = ny;
-            orientation = no;
-            if i < turns.len() {
-                orientation = rotate(orientation, turns[i]);
+        Ok(walk(input, step).into())
+    }
+
+    fn part2(&self, input: &str) -> PartResult {
+        let (field, _, _, _) = parse(input);
+        let wraps = fold(&field)?;
+
+        // step on, over to the face an edge is glued to when walking off one
+        let step = |x: i64, y: i64, orientation: i64| match wraps.get(&(x, y, orientation)) {
+            Some(&wrapped) => wrapped,
+            None => {
+                let (dx, dy) = DELTAS[orientation as usize];
+                (x + dx, y + dy, orientation)
             }
-        }
+        };
 
-        let answer = 1000 * (y + 1) + 4 * (x + 1) + orientation;
-        Some(answer.to_string())
+        Ok(walk(input, step).into())
     }
 }
@@ -1,7 +1,9 @@
 //! Auto-translated from the original Python solution by an LLM (Claude Code).
 //! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2023/tree/master/05
+use crate::util::intervals::RangeSet;
 use crate::util::Day;
+use std::ops::Range;
 
 pub struct D5;
 
@@ -82,56 +84,18 @@ impl Day for D5 {
     fn solve_part2(&self, input: &str) -> Option<String> {
         let (seeds, maps) = parse(input);
 
-        let mut seed_ranges: Vec<(i64, i64)> = seeds
-            .chunks(2)
-            .map(|c| (c[0], c[0] + c[1]))
-            .collect();
+        let mut seed_ranges: RangeSet = seeds.chunks(2).map(|c| c[0]..c[0] + c[1]).collect();
 
         for (_f, _t, ranges) in maps.iter() {
-            let mut new_seed_ranges: Vec<(i64, i64)> = Vec::new();
-
-            // NOTE: faithfully mirrors the Python which appends to seed_ranges
-            // while iterating with an index, so the list grows during iteration.
-            let mut idx = 0;
-            while idx < seed_ranges.len() {
-                let (sl, sh) = seed_ranges[idx];
-
-                let mut matched = false;
-                for &(l, h, ts) in ranges.iter() {
-                    // no overlap - continue
-                    if sh < l || h < sl {
-                        continue;
-                    }
-
-                    // seeds are all within range - no split, just map
-                    if l <= sl && sl <= sh && sh <= h {
-                        new_seed_ranges.push((ts + (sl - l), ts + (sh - l)));
-                        matched = true;
-                        break;
-                    } else {
-                        // otherwise split into parts, add to seed ranges and continue
-                        if sl < l {
-                            seed_ranges.push((sl, l - 1));
-                        }
-                        if h < sh {
-                            seed_ranges.push((h + 1, sh));
-                        }
-                        seed_ranges.push((sl.max(l), sh.min(h)));
-                        matched = true;
-                        break;
-                    }
-                }
-                if !matched {
-                    new_seed_ranges.push((sl, sh));
-                }
-
-                idx += 1;
-            }
+            let rules: Vec<(Range<i64>, i64)> = ranges
+                .iter()
+                .map(|&(l, h, ts)| (l..h, ts - l))
+                .collect();
 
-            seed_ranges = new_seed_ranges;
+            seed_ranges = seed_ranges.map_through(&rules);
         }
 
-        let answer = seed_ranges.iter().map(|&(l, _)| l).min().unwrap();
+        let answer = seed_ranges.ranges()[0].start;
         Some(answer.to_string())
     }
 }
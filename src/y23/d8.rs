@@ -1,6 +1,7 @@
 //! Auto-translated from the original Python solution by an LLM (Claude Code).
 //! Not yet hand-reviewed or rewritten for idiomatic Rust / performance.
 //! Original: https://github.com/xiaoxiae/Advent-of-Code-2023/tree/master/08
+use crate::util::math::lcm;
 use crate::util::Day;
 use rustc_hash::FxHashMap;
 
@@ -29,14 +30,6 @@ fn parse(input: &str) -> (Vec<u8>, FxHashMap<String, (String, String)>) {
     (instructions, graph)
 }
 
-fn gcd(a: u64, b: u64) -> u64 {
-    if b == 0 { a } else { gcd(b, a % b) }
-}
-
-fn lcm(a: u64, b: u64) -> u64 {
-    a / gcd(a, b) * b
-}
-
 impl Day for D8 {
     fn solve_part1(&self, input: &str) -> Option<String> {
         let (instructions, graph) = parse(input);
@@ -84,7 +77,7 @@ impl Day for D8 {
             }
         }
 
-        let result = steps.into_iter().fold(1u64, lcm);
+        let result = steps.into_iter().fold(1, |a, b| lcm(a, b as i64));
         Some(result.to_string())
     }
 }
@@ -1,36 +1,45 @@
-use crate::util::Day;
+use crate::util::{Error, PartResult, Solution};
 use itertools::Itertools;
 use std::collections::HashMap;
 
 pub struct D1;
 
-fn parse_input(input: &str) -> (Vec<usize>, Vec<usize>) {
-    input
+fn parse_input(input: &str) -> Result<(Vec<usize>, Vec<usize>), Error> {
+    let number = |n: &str| {
+        n.parse::<usize>()
+            .map_err(|_| Error::parse_in(input, n, "expected a number"))
+    };
+
+    let pairs = input
         .trim()
         .lines()
         .map(|l| {
-            l.split_whitespace()
-                .map(|n| n.parse::<usize>().unwrap())
+            let (a, b) = l
+                .split_whitespace()
                 .collect_tuple()
-                .unwrap()
+                .ok_or_else(|| Error::parse_in(input, l, "expected two numbers"))?;
+
+            Ok((number(a)?, number(b)?))
         })
-        .unzip()
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(pairs.into_iter().unzip())
 }
 
-impl Day for D1 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        let (mut l, mut r) = parse_input(input);
+impl Solution for D1 {
+    fn part1(&self, input: &str) -> PartResult {
+        let (mut l, mut r) = parse_input(input)?;
 
         l.sort_unstable();
         r.sort_unstable();
 
         let differences: usize = l.iter().zip(r.iter()).map(|(x, y)| x.abs_diff(*y)).sum();
 
-        Option::from(differences.to_string())
+        Ok(differences.into())
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
-        let (l, r) = parse_input(input);
+    fn part2(&self, input: &str) -> PartResult {
+        let (l, r) = parse_input(input)?;
 
         let mut occurrences: HashMap<usize, usize> = HashMap::new();
 
@@ -38,19 +47,17 @@ impl Day for D1 {
             *occurrences.entry(item).or_insert(0) += 1;
         }
 
-        Option::from(
-            l.iter()
-                .map(|x| x * *occurrences.get(x).unwrap_or(&0))
-                .sum::<usize>()
-                .to_string(),
-        )
+        Ok(l.iter()
+            .map(|x| x * *occurrences.get(x).unwrap_or(&0))
+            .sum::<usize>()
+            .into())
     }
 
     /// --- Tom's Part 3 ---
     /// For occurrences of number i in list 1 and 2, calculate the distances between
     /// the respective pairings such that it is minimized. Return their sum.
-    fn solve_part3(&self, input: &str) -> Option<String> {
-        let lists = parse_input(input);
+    fn part3(&self, input: &str) -> PartResult {
+        let lists = parse_input(input)?;
 
         let mut occurrences: Vec<HashMap<usize, Vec<usize>>> = Vec::new();
 
@@ -89,6 +96,6 @@ impl Day for D1 {
             distances += min_distance;
         }
 
-        Option::from(distances.to_string())
+        Ok(distances.into())
     }
 }
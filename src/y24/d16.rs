@@ -1,34 +1,32 @@
-use crate::util::Day;
-use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, VecDeque};
+use crate::util::search::{self, Paths};
+use crate::util::{Error, ParsedSolution, PartResult};
+use rustc_hash::FxHashSet as HashSet;
 
 type Map = Vec<Vec<char>>;
 type Position = (usize, usize);
 type Direction = (isize, isize);
 type Distance = usize;
 
-type DistanceResults = HashMap<(Position, Direction), Distance>;
-type PathResults = HashMap<(Position, Direction), Vec<(Position, Direction)>>;
+type Node = (Position, Direction);
 
-fn parse_input(input: &str) -> (Map, Position, Position) {
+fn parse_input(input: &str) -> Result<(Map, Position, Position), Error> {
     let mut map = input
         .trim()
         .split_whitespace()
         .map(|s| s.chars().collect())
         .collect::<Map>();
 
-    let mut start: Position = (0, 0);
-    let mut end: Position = (0, 0);
+    let mut start: Option<Position> = None;
+    let mut end: Option<Position> = None;
     for y in 0..map.len() {
         for x in 0..map[y].len() {
             match map[y][x] {
                 'S' => {
-                    start = (x, y);
+                    start = Some((x, y));
                     map[y][x] = '.';
                 }
                 'E' => {
-                    end = (x, y);
+                    end = Some((x, y));
                     map[y][x] = '.';
                 }
                 _ => continue,
@@ -36,7 +34,10 @@ fn parse_input(input: &str) -> (Map, Position, Position) {
         }
     }
 
-    (map, start, end)
+    let start = start.ok_or_else(|| Error::parse_at(input, 0, "the maze has no start"))?;
+    let end = end.ok_or_else(|| Error::parse_at(input, 0, "the maze has no end"))?;
+
+    Ok((map, start, end))
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -104,106 +105,61 @@ impl State {
     }
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.distance.cmp(&self.distance) // reverse the order to make it a min-heap
-    }
-}
-
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-fn solve(
-    map: Map,
-    start: Position,
-    end: Position,
-) -> (Option<Distance>, DistanceResults, PathResults) {
-    let mut distances: DistanceResults = HashMap::default();
-    let mut paths: PathResults = HashMap::default();
-
-    let mut heap = BinaryHeap::new();
-
-    distances.insert((start, (1, 0)), 0);
-    paths.insert((start, (1, 0)), vec![]);
-
-    heap.push(State {
-        position: start,
-        direction: (1, 0),
-        distance: 0,
-    });
-
-    let mut end_distance = None;
-
-    while let Some(state) = heap.pop() {
-        if state.position == end {
-            end_distance = Option::from(state.distance);
-        }
-
-        // Get the next possible positions and distances
-        for next_state in state.next_states(&map) {
-            // Skip processing if we have already found a shorter distance
-            let best_distance = *distances
-                .get(&(next_state.position, next_state.direction))
-                .unwrap_or(&usize::MAX);
-
-            // Skip states after finding the end distance
-            if next_state.distance > end_distance.unwrap_or(usize::MAX) {
-                continue;
-            }
-
-            // New/improved
-            if next_state.distance < best_distance {
-                distances.insert(
-                    (next_state.position, next_state.direction),
-                    next_state.distance,
-                );
-                paths.insert(
-                    (next_state.position, next_state.direction),
-                    Vec::from([(state.position, state.direction)]),
-                );
-
-                heap.push(next_state);
-            } else if next_state.distance == best_distance {
-                paths
-                    .entry((next_state.position, next_state.direction))
-                    .or_insert_with(Vec::new)
-                    .push((state.position, state.direction));
-            }
-        }
-    }
-
-    (end_distance, distances, paths)
+/// Finds all of the shortest paths from the start (facing east) to the end.
+fn solve(map: &Map, start: Position, end: Position) -> Paths<Node, Distance> {
+    search::dijkstra_all(
+        [(start, (1, 0))],
+        |&(position, direction): &Node| {
+            let state = State {
+                position,
+                direction,
+                distance: 0,
+            };
+
+            state
+                .next_states(map)
+                .into_iter()
+                .map(|next| ((next.position, next.direction), next.distance))
+        },
+        |&(position, _)| position == end,
+    )
 }
 
 pub struct D16;
 
-impl Day for D16 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        let (map, start, end) = parse_input(input);
-        let (end_distance, _, _) = solve(map, start, end);
+impl ParsedSolution for D16 {
+    type Parsed = (Map, Position, Position);
 
-        Option::from(end_distance.unwrap().to_string())
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        parse_input(input)
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
-        let (map, start, end) = parse_input(input);
-        let (_, _, paths) = solve(map, start, end);
-
-        let mut coordinates = HashSet::default();
-        let mut deque =
-            VecDeque::from([(end, (0, -1)), (end, (0, 1)), (end, (-1, 0)), (end, (1, 0))]);
-
-        while let Some((pos, dir)) = deque.pop_front() {
-            coordinates.insert(pos);
-
-            for neighbor in paths.get(&(pos, dir)).unwrap_or(&vec![]) {
-                deque.push_back(*neighbor);
-            }
-        }
+    fn part1(&self, (map, start, end): &Self::Parsed) -> PartResult {
+        solve(map, *start, *end)
+            .goal_distance()
+            .map(|distance| distance.into())
+            .ok_or_else(|| Error::Unsolvable("the end can't be reached".to_string()))
+    }
 
-        Option::from(coordinates.len().to_string())
+    fn part2(&self, (map, start, end): &Self::Parsed) -> PartResult {
+        let paths = solve(map, *start, *end);
+        let end_distance = paths
+            .goal_distance()
+            .ok_or_else(|| Error::Unsolvable("the end can't be reached".to_string()))?;
+
+        // the end can be reached facing any direction
+        let ends: Vec<Node> = [(0, -1), (0, 1), (-1, 0), (1, 0)]
+            .into_iter()
+            .map(|direction| (*end, direction))
+            .filter(|node| paths.distance(node) == Some(end_distance))
+            .collect();
+
+        let coordinates: HashSet<Position> = paths
+            .on_shortest_paths(&ends)
+            .into_iter()
+            .map(|(position, _)| position)
+            .collect();
+
+        Ok(coordinates.len().into())
     }
 }
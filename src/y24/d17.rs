@@ -1,72 +1,20 @@
-use crate::util::Day;
+use crate::util::vm::chronospatial::{self, Chronospatial};
+use crate::util::vm::Machine;
+use crate::util::{Error, PartResult, Solution};
 use itertools::Itertools;
-use regex::Regex;
 use rand::{rng, Rng};
 use rayon::prelude::*;
 
-type Registers = Vec<usize>;
-type Instructions = Vec<usize>;
+/// Runs the program with register A set to `a` (and the others to 0).
+fn run(machine: &Machine<Chronospatial>, a: i64) -> Vec<i64> {
+    let mut machine = machine.clone().with_registers(&[a, 0, 0]);
 
-static A: usize = 0;
-static B: usize = 1;
-static C: usize = 2;
-
-
-fn parse_input(input: &str) -> (Registers, Instructions) {
-    let parts = input.trim().split("\n\n").collect::<Vec<_>>();
-
-    let re = Regex::new(r"\d+").unwrap();
-
-    let registers = re.find_iter(parts[0])
-        .filter_map(|mat| mat.as_str().parse::<usize>().ok())
-        .collect::<Vec<usize>>();
-
-    let instructions = re.find_iter(parts[1])
-        .filter_map(|mat| mat.as_str().parse::<usize>().ok())
-        .collect::<Vec<usize>>();
-
-    (registers, instructions)
+    machine.run_collect()
 }
 
-fn combo(val: usize, registers: &Registers) -> usize {
-    if val <= 3 {
-        val
-    } else {
-        assert_ne!(val, 7);
-
-        registers[val - 4]
-    }
-}
-
-fn run(registers: &mut Registers, instructions: &Instructions) -> Vec<usize> {
-    let mut ip: usize = 0;
-    let mut out: Vec<usize> = Vec::new();
-
-    while ip < instructions.len() {
-        let opt = instructions[ip];
-        let val = instructions[ip + 1];
-
-        match opt {
-            0 => registers[A] = registers[A] / (1 << combo(val, &registers)),
-            1 => registers[B] ^= val,
-            2 => registers[B] = combo(val, &registers) % 8,
-            3 => {
-                if registers[A] != 0 {
-                    ip = val;
-                    continue;
-                }
-            }
-            4 => registers[B] = registers[B] ^ registers[C],
-            5 => out.push(combo(val, &registers) % 8),
-            6 => registers[B] = registers[A] / (1 << combo(val, &registers)),
-            7 => registers[C] = registers[A] / (1 << combo(val, &registers)),
-            _ => panic!("Unknown instruction '{}'!", opt),
-        }
-
-        ip += 2;
-    }
-
-    out
+/// The program as the numbers it consists of, which it should output as a quine.
+fn numbers(machine: &Machine<Chronospatial>) -> Vec<i64> {
+    machine.program.iter().flat_map(|instruction| instruction.numbers()).collect()
 }
 
 /// When running the program with increasing As, there is a pattern:
@@ -74,19 +22,23 @@ fn run(registers: &mut Registers, instructions: &Instructions) -> Vec<usize> {
 /// - when looping the digit doesn't change the digits after it
 ///
 /// Combining these two facts, we can loop digits from the end and recurse.
-fn recursive(value: usize, power: usize, instructions: &Instructions) -> Option<usize> {
+fn recursive(
+    value: i64,
+    power: usize,
+    machine: &Machine<Chronospatial>,
+    instructions: &[i64],
+) -> Option<i64> {
     for i in 0..(1 << 3) {
         let new_value = value + i * (1 << (3 * power));
 
-        let mut registers = vec![new_value, 0, 0];
-        let out = run(&mut registers, &instructions);
+        let out = run(machine, new_value);
 
         if out.get(power) == instructions.get(power) {
             if power == 0 {
                 return Some(new_value);
             }
 
-            if let Some(v) = recursive(new_value, power - 1, instructions) {
+            if let Some(v) = recursive(new_value, power - 1, machine, instructions) {
                 return Some(v);
             }
         }
@@ -96,15 +48,14 @@ fn recursive(value: usize, power: usize, instructions: &Instructions) -> Option<
 }
 
 /// Flip a random bit
-fn _mutate(value: usize, bits: usize) -> usize {
+fn _mutate(value: i64, bits: usize) -> i64 {
     let bit_to_flip = rng().random_range(0..bits);
     value ^ (1 << bit_to_flip)
 }
 
 /// Fitness of an individual is how many places it matches with instructions
-fn _fitness(value: usize, instructions: &Instructions) -> usize {
-    let mut registers = vec![value, 0, 0];
-    let out = run(&mut registers, &instructions);
+fn _fitness(value: i64, machine: &Machine<Chronospatial>, instructions: &[i64]) -> usize {
+    let out = run(machine, value);
 
     out.iter().zip(instructions.iter())
         .filter(|(&i, &j)| i == j)
@@ -112,18 +63,18 @@ fn _fitness(value: usize, instructions: &Instructions) -> usize {
 }
 
 /// A genetic solution that randomly flips bits of the register A until we get a quine
-fn _genetic(instructions: &Instructions) -> Option<usize> {
+fn _genetic(machine: &Machine<Chronospatial>, instructions: &[i64]) -> Option<i64> {
     const PATIENCE: usize = 3_200;  // how many generations without improvement we wait
     const POOL_SIZE: usize = 1_024;  // generation size
 
-    let mut pool: Vec<usize> = vec![0; POOL_SIZE];
+    let mut pool: Vec<i64> = vec![0; POOL_SIZE];
 
-    let mut min_a = usize::MAX;
+    let mut min_a = i64::MAX;
     let mut generation = 0;
     loop {
         // calculate fitness
-        let mut values: Vec<(usize, usize)> = pool.par_iter()
-            .map(|v| (_fitness(*v, instructions), *v))
+        let mut values: Vec<(usize, i64)> = pool.par_iter()
+            .map(|v| (_fitness(*v, machine, instructions), *v))
             .collect::<Vec<_>>();
 
         values.sort_by_key(|v| v.0);
@@ -137,11 +88,11 @@ fn _genetic(instructions: &Instructions) -> Option<usize> {
 
         // kill half the population, mutating the remaining ones
         let mut offspring = values.iter().map(|v| v.1)
-            .collect::<Vec<usize>>();
+            .collect::<Vec<i64>>();
 
         offspring = offspring[offspring.len() / 2..].to_owned()
             .iter().flat_map(|&v| [_mutate(v, instructions.len() * 3), _mutate(v, instructions.len() * 3)])
-            .collect::<Vec<usize>>();
+            .collect::<Vec<i64>>();
 
         pool = offspring;
 
@@ -153,33 +104,34 @@ fn _genetic(instructions: &Instructions) -> Option<usize> {
     }
 
     match min_a {
-        usize::MAX => None,
+        i64::MAX => None,
         v => Option::from(v),
     }
 }
 
 pub struct D17;
 
-impl Day for D17 {
-    fn solve_part1(&self, input: &str) -> Option<String> {
-        let (mut registers, instructions) = parse_input(input);
+impl Solution for D17 {
+    fn part1(&self, input: &str) -> PartResult {
+        let mut machine = chronospatial::parse(input)?;
 
-        let out = run(&mut registers, &instructions);
+        let out = machine.run_collect();
 
-        Option::from(out.iter().map(|v| v.to_string()).join(","))
+        Ok(out.iter().map(|v| v.to_string()).join(",").into())
     }
 
-    fn solve_part2(&self, input: &str) -> Option<String> {
-        let (_, instructions) = parse_input(input);
+    fn part2(&self, input: &str) -> PartResult {
+        let machine = chronospatial::parse(input)?;
+        let instructions = numbers(&machine);
 
         // I was bored, so I also solved it with a genetic algorithm
         //
-        // let a = _genetic(&instructions)
+        // let a = _genetic(&machine, &instructions)
         //     .expect("No A value found!");
 
-        let a = recursive(0, instructions.len() - 1, &instructions)
-            .expect("No A value found!");
+        let a = recursive(0, instructions.len() - 1, &machine, &instructions)
+            .ok_or_else(|| Error::Unsolvable("No A value found!".to_string()))?;
 
-        Option::from(a.to_string())
+        Ok(a.into())
     }
 }
@@ -1,14 +1,10 @@
+use crate::util::grid::{Grid, Point};
+use crate::util::search::{self, Paths};
 use crate::util::Day;
 use regex::Regex;
-use std::collections::VecDeque;
+use rustc_hash::FxHashSet;
 
-type Position = (usize, usize);
-type Distance = usize;
-
-type Bytes = Vec<Position>;
-type Map = Vec<Vec<bool>>;
-
-fn parse_input(input: &str) -> Bytes {
+fn parse_input(input: &str) -> Vec<Point> {
     let re = Regex::new(r"\d+").unwrap();
 
     let numbers = re
@@ -22,66 +18,26 @@ fn parse_input(input: &str) -> Bytes {
         .collect()
 }
 
-#[derive(Debug)]
-struct State {
-    position: Position,
-    distance: Distance,
-}
-
-impl State {
-    fn next_states(&self, map: &Map) -> Vec<State> {
-        [(1, 0), (0, 1), (-1, 0), (0, -1)].iter().map(|(dx, dy)| {
-            let (x, y) = self.position;
-
-            let nx = x as isize + dx;
-            let ny = y as isize + dy;
-
-            if !(0 <= ny && ny < map.len() as isize) || !(0 <= nx && nx < map[ny as usize].len() as isize) {
-                None
-            } else {
-                let p: Position = (nx as usize, ny as usize);
-
-                if map[p.1][p.0] {
-                    None
-                } else {
-                    Option::from(State {
-                        position: p,
-                        distance: self.distance + 1,
-                    })
-                }
-            }
-        }
-        ).filter_map(|v| v).collect::<Vec<_>>()
+/// The size of the memory space and how many bytes fall in part 1, which are
+/// smaller for the examples (128 is an arbitrary cut-off between the two).
+fn dimensions(bytes: &[Point]) -> (usize, usize) {
+    if bytes.len() <= 128 {
+        (7, 12)
+    } else {
+        (71, 1024)
     }
 }
 
-fn solve(
-    map: &Map,
-    start: Position,
-    end: Position,
-) -> Option<Distance> {
-    let mut explored: Vec<Vec<bool>> = vec![vec![false; map[0].len()]; map.len()];
-    let mut queue = VecDeque::new();
-
-    explored[start.1][start.0] = true;
-    queue.push_back(State { position: start, distance: 0 });
+/// The shortest paths from the top left corner, up to the bottom right one.
+fn escape(corrupted: &Grid<bool>) -> Paths<Point, usize> {
+    let exit = (corrupted.width() - 1, corrupted.height() - 1);
+    let neighbours = |&point: &Point| {
+        corrupted
+            .neighbours4(point)
+            .filter(|&next| !corrupted[next])
+    };
 
-    while let Some(state) = queue.pop_front() {
-        if state.position == end {
-            return Option::from(state.distance);
-        }
-
-        for next_state in state.next_states(&map) {
-            let &(x, y) = &next_state.position;
-            // New/improved
-            if !explored[y][x] {
-                explored[y][x] = true;
-                queue.push_back(next_state);
-            }
-        }
-    }
-
-    None
+    search::bfs((0, 0), neighbours, |&point| point == exit)
 }
 
 pub struct D18;
@@ -89,51 +45,39 @@ pub struct D18;
 impl Day for D18 {
     fn solve_part1(&self, input: &str) -> Option<String> {
         let bytes = parse_input(input);
+        let (size, fallen_bytes) = dimensions(&bytes);
 
-        // (128 is arbitrarily set for testing instances)
-        let (width, height, fallen_bytes) = if bytes.len() <= 128 { (7, 7, 12) } else { (71, 71, 1024) };
+        let mut corrupted = Grid::new(size, size, false);
 
-        let mut map: Vec<Vec<bool>> = vec![vec![false; width]; height];
-
-        for fallen_byte in 0..fallen_bytes {
-            let (x, y) = bytes[fallen_byte];
-            map[y][x] = true;
+        for &byte in &bytes[..fallen_bytes] {
+            corrupted[byte] = true;
         }
 
-        let distance = solve(&map, (0, 0), (width - 1, height - 1));
+        let distance = escape(&corrupted).goal_distance();
 
         Option::from(distance.expect("No path found!").to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Option<String> {
         let bytes = parse_input(input);
+        let (size, _) = dimensions(&bytes);
 
-        // (128 is arbitrarily set for testing instances)
-        let (width, height) = if bytes.len() <= 128 { (7, 7) } else { (71, 71) };
-
-        let mut lo = 0;
-        let mut hi = bytes.len();
+        let mut corrupted = Grid::new(size, size, false);
+        let mut path: FxHashSet<Point> = escape(&corrupted).goal_path()?.into_iter().collect();
 
-        while lo < hi {
-            let mut map: Vec<Vec<bool>> = vec![vec![false; width]; height];
-            let mid = (lo + hi) / 2;
+        // only a byte falling onto the path can cut the exit off, and then there may
+        // still be another way around
+        for &(x, y) in &bytes {
+            corrupted[(x, y)] = true;
 
-            for fallen_byte in 0..=mid {
-                let (x, y) = bytes[fallen_byte];
-                map[y][x] = true;
-            }
-
-            match solve(&map, (0, 0), (width - 1, height - 1)) {
-                Some(_) => lo = mid + 1,
-                None => hi = mid,
+            if path.contains(&(x, y)) {
+                match escape(&corrupted).goal_path() {
+                    Some(detour) => path = detour.into_iter().collect(),
+                    None => return Option::from(format!("{},{}", x, y)),
+                }
             }
         }
 
-        if lo == bytes.len() {
-            panic!("Path is never blocked!")
-        }
-
-        let (x, y) = bytes[lo];
-        return Option::from(format!("{},{}", x, y).to_string());
+        panic!("Path is never blocked!")
     }
 }
@@ -1,3 +1,4 @@
+use crate::util::grid::{Grid, Point, DIRECTIONS_4};
 use crate::util::Day;
 use rayon::prelude::*;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -5,42 +6,24 @@ use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet };
 
 pub struct D6;
 
-static DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
-
-fn parse_map(input: &str) -> (Vec<Vec<char>>, (usize, usize)) {
-    let map = input
-        .trim()
-        .split_whitespace()
-        .map(|line| line.chars().collect::<Vec<char>>())
-        .collect::<Vec<Vec<char>>>();
-
-    let mut start = (0, 0);
-    for y in 0..map.len() {
-        for x in 0..map[y].len() {
-            if map[y][x] == '^' {
-                start = (x, y);
-            }
-        }
-    }
+fn parse_map(input: &str) -> (Grid<char>, Point) {
+    let map = Grid::parse(input.trim()).unwrap();
+    let start = map.find(&'^').unwrap();
 
     (map, start)
 }
 
 /// Move forward on the map, marking the directions
-fn mark_forward(
-    map: &mut Vec<Vec<char>>,
-    position: (usize, usize),
-    direction: usize,
-) -> Option<(usize, usize)> {
-    let (dx, dy) = DIRECTIONS[direction];
+fn mark_forward(map: &mut Grid<char>, position: Point, direction: usize) -> Option<Point> {
+    let (dx, dy) = DIRECTIONS_4[direction];
     let (mut x, mut y) = position;
 
     loop {
-        map[y][x] = 'X';
+        map[(x, y)] = 'X';
 
-        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+        let (nx, ny) = (x as isize + dx, y as isize + dy);
 
-        match map.get(ny as usize).and_then(|row| row.get(nx as usize)) {
+        match map.get(nx, ny) {
             None => return None,
             Some('#') => return Some((x, y)),
             Some(_) => (x, y) = (nx as usize, ny as usize),
@@ -50,22 +33,22 @@ fn mark_forward(
 
 /// Move forward on the map, taking an extra barrier into account
 fn move_forward(
-    map: &Vec<Vec<char>>,
-    position: (usize, usize),
+    map: &Grid<char>,
+    position: Point,
     direction: usize,
-    extra_barrier: (usize, usize),
-) -> Option<(usize, usize)> {
-    let (dx, dy) = DIRECTIONS[direction];
+    extra_barrier: Point,
+) -> Option<Point> {
+    let (dx, dy) = DIRECTIONS_4[direction];
     let (mut x, mut y) = position;
 
     loop {
-        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+        let (nx, ny) = (x as isize + dx, y as isize + dy);
 
         if (nx as usize, ny as usize) == extra_barrier {
             return Some((x, y));
         }
 
-        match map.get(ny as usize).and_then(|row| row.get(nx as usize)) {
+        match map.get(nx, ny) {
             None => return None,
             Some('#') => return Some((x, y)),
             Some(_) => (x, y) = (nx as usize, ny as usize),
@@ -85,17 +68,13 @@ impl Day for D6 {
             match result {
                 Some(p) => {
                     start = p;
-                    direction = (direction + 1) % DIRECTIONS.len();
+                    direction = (direction + 1) % DIRECTIONS_4.len();
                 }
                 None => break,
             }
         }
 
-        Option::from(map.iter()
-            .flatten()
-            .filter(|&&c| c == 'X')
-            .count()
-            .to_string())
+        Option::from(map.find_all(&'X').count().to_string())
     }
 
     fn solve_part2(&self, input: &str) -> Option<String> {
@@ -106,7 +85,7 @@ impl Day for D6 {
 
         // Use part 1 to get possible barrel positions, as well as shortcuts
         let mut direction = initial_direction;
-        let mut shortcuts: HashMap<((usize, usize), usize), ((usize, usize), usize)> =
+        let mut shortcuts: HashMap<(Point, usize), (Point, usize)> =
             HashMap::default();
 
         loop {
@@ -114,10 +93,10 @@ impl Day for D6 {
 
             match result {
                 Some(p) => {
-                    shortcuts.insert((start, direction), (p, (direction + 1) % DIRECTIONS.len()));
+                    shortcuts.insert((start, direction), (p, (direction + 1) % DIRECTIONS_4.len()));
 
                     start = p;
-                    direction = (direction + 1) % DIRECTIONS.len();
+                    direction = (direction + 1) % DIRECTIONS_4.len();
                 }
                 None => break,
             }
@@ -126,22 +105,13 @@ impl Day for D6 {
         let valid_obstructions = AtomicUsize::new(0);
 
         // Go through all barrel positions and simulate (tehehe)
-        let positions: Vec<(usize, usize)> = map
-            .iter()
-            .enumerate()
-            .flat_map(|(y, row)| {
-                row.iter()
-                    .enumerate()
-                    .filter(|&(_, &cell)| cell == 'X')
-                    .map(move |(x, _)| (x, y))
-            })
-            .collect();
+        let positions: Vec<Point> = map.find_all(&'X').collect();
 
         // Process each (x, y) in parallel
         positions.par_iter().for_each(|&(x, y)| {
             let mut start = initial_start.clone();
             let mut direction = 0;
-            let mut reached_states: HashSet<((usize, usize), usize)> = HashSet::default();
+            let mut reached_states: HashSet<(Point, usize)> = HashSet::default();
 
             loop {
                 // Use shortcuts if possible
@@ -155,7 +125,7 @@ impl Day for D6 {
 
                 if let Some(p) = move_forward(&map, start, direction, (x, y)) {
                     start = p;
-                    direction = (direction + 1) % DIRECTIONS.len();
+                    direction = (direction + 1) % DIRECTIONS_4.len();
                 } else {
                     break;
                 }